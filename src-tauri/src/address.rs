@@ -0,0 +1,360 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::ExampleResult;
+
+// Bộ rule mặc định đi kèm app, được ghi ra thư mục config ở lần chạy đầu
+const DEFAULT_RULES_JSON: &str = include_str!("../resources/address_rules.json");
+
+// Tiền tố hành chính: các cách viết (aliases) được đưa về dạng chuẩn (canonical)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrefixRule {
+    pub canonical: String,
+    pub aliases: Vec<String>,
+}
+
+// Từ/cụm từ viết tắt được thay bằng dạng đầy đủ (so khớp nguyên từ)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Abbreviation {
+    pub to: String,
+    pub from: Vec<String>,
+}
+
+// Format file address_rules.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AddressRulesFile {
+    version: u32,
+    // Tiền tố bị bỏ khỏi st_name ("Đường", "Đ.", ...)
+    #[serde(default)]
+    street_prefixes: Vec<String>,
+    #[serde(default)]
+    admin_prefixes: Vec<PrefixRule>,
+    #[serde(default)]
+    abbreviations: Vec<Abbreviation>,
+    // Viết hoa chữ cái đầu mỗi từ cho các phần viết toàn chữ thường/toàn chữ hoa
+    #[serde(default)]
+    title_case: bool,
+}
+
+// Chuẩn hóa địa chỉ tiếng Việt (NFC, tiền tố hành chính, viết tắt, viết hoa)
+pub struct AddressNormalizer {
+    rules: AddressRulesFile,
+    // Alias của mọi tiền tố, sắp alias dài trước để "TP." được ưu tiên hơn "T."
+    aliases: Vec<(String, String)>,
+}
+
+impl AddressNormalizer {
+    pub fn builtin() -> Self {
+        let rules: AddressRulesFile =
+            serde_json::from_str(DEFAULT_RULES_JSON).expect("built-in address_rules.json is invalid");
+        Self::new(rules)
+    }
+
+    // Load từ address_rules.json, chưa có thì ghi bộ rule mặc định ra, lỗi thì dùng mặc định
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let Some(path) = path else {
+            return Self::builtin();
+        };
+
+        if !path.exists() {
+            write_default(&path);
+            return Self::builtin();
+        }
+
+        let loaded = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<AddressRulesFile>(&content).map_err(|e| e.to_string()));
+
+        match loaded {
+            Ok(rules) => {
+                println!("Loaded address rules v{} from {:?}", rules.version, path);
+                Self::new(rules)
+            }
+            Err(e) => {
+                println!("Error loading address rules: {}, using built-in rules", e);
+                Self::builtin()
+            }
+        }
+    }
+
+    fn new(rules: AddressRulesFile) -> Self {
+        let mut aliases: Vec<(String, String)> = rules
+            .admin_prefixes
+            .iter()
+            .flat_map(|rule| {
+                rule.aliases
+                    .iter()
+                    .map(|alias| (nfc(alias), nfc(&rule.canonical)))
+            })
+            .collect();
+        aliases.sort_by_key(|(alias, _)| std::cmp::Reverse(alias.chars().count()));
+
+        Self { rules, aliases }
+    }
+
+    // Chuẩn hóa các field địa chỉ của result
+    pub fn apply(&self, result: &mut ExampleResult) {
+        result.st_name = result.st_name.as_deref().map(|s| self.street_name(s)).filter(|s| !s.is_empty());
+        result.buaname = result.buaname.as_deref().map(|s| self.part(s)).filter(|s| !s.is_empty());
+        result.sub_com = result.sub_com.as_deref().map(|s| self.part(s)).filter(|s| !s.is_empty());
+        result.province = result.province.as_deref().map(|s| self.part(s)).filter(|s| !s.is_empty());
+        result.district = result.district.as_deref().map(|s| self.part(s)).filter(|s| !s.is_empty());
+        result.ward = result.ward.as_deref().map(|s| self.part(s)).filter(|s| !s.is_empty());
+        result.address = self.address(&result.address);
+    }
+
+    // Tên đường: chuẩn hóa rồi bỏ tiền tố "Đường"/"Đ."
+    pub fn street_name(&self, name: &str) -> String {
+        let name = self.part(name);
+
+        for prefix in &self.rules.street_prefixes {
+            let prefix = nfc(prefix);
+            if let Some(rest) = strip_word_prefix(&name, &prefix) {
+                if !rest.is_empty() {
+                    return rest.to_string();
+                }
+            }
+        }
+
+        name
+    }
+
+    // Địa chỉ đầy đủ: chuẩn hóa từng phần phân tách bởi dấu phẩy
+    pub fn address(&self, address: &str) -> String {
+        address
+            .split(',')
+            .map(|part| self.part(part))
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // Chuẩn hóa một phần địa chỉ (tên phường, quận, ... hoặc một đoạn của address)
+    fn part(&self, text: &str) -> String {
+        let text = nfc(text).split_whitespace().collect::<Vec<_>>().join(" ");
+
+        // Viết hoa trước để "tp hcm", "p12" khớp được alias "Tp", "P"
+        let text = if self.rules.title_case && is_single_case(&text) {
+            title_case(&text)
+        } else {
+            text
+        };
+
+        let text = self.expand_prefix(&text);
+        self.expand_abbreviations(&text)
+    }
+
+    // "P.12" -> "Phường 12", "Q1" -> "Quận 1", "TP.HCM" -> "Thành phố HCM"
+    fn expand_prefix(&self, text: &str) -> String {
+        for (alias, canonical) in &self.aliases {
+            let Some(rest) = text.strip_prefix(alias.as_str()) else {
+                continue;
+            };
+            let rest_trimmed = rest.trim_start();
+            if rest_trimmed.is_empty() {
+                continue;
+            }
+
+            let next = rest.chars().next().unwrap_or(' ');
+            if alias.ends_with('.') || next.is_whitespace() || next.is_ascii_digit() {
+                return format!("{} {}", canonical, rest_trimmed);
+            }
+        }
+
+        text.to_string()
+    }
+
+    // Thay các từ viết tắt (so khớp nguyên từ, không phân biệt hoa thường, có thể nhiều từ)
+    fn expand_abbreviations(&self, text: &str) -> String {
+        let mut words: Vec<String> = text.split(' ').map(|w| w.to_string()).collect();
+
+        for abbreviation in &self.rules.abbreviations {
+            for from in &abbreviation.from {
+                let from_words: Vec<String> = nfc(from).split_whitespace().map(|w| w.to_lowercase()).collect();
+                if from_words.is_empty() {
+                    continue;
+                }
+
+                let mut i = 0;
+                while i + from_words.len() <= words.len() {
+                    let matches = words[i..i + from_words.len()]
+                        .iter()
+                        .zip(&from_words)
+                        .all(|(word, from_word)| word.to_lowercase() == *from_word);
+                    if matches {
+                        words.splice(i..i + from_words.len(), [nfc(&abbreviation.to)]);
+                    }
+                    i += 1;
+                }
+            }
+        }
+
+        words.join(" ")
+    }
+}
+
+fn nfc(text: &str) -> String {
+    text.nfc().collect()
+}
+
+// Bỏ tiền tố nếu nó là nguyên một từ (theo sau là khoảng trắng) hoặc kết thúc bằng "."
+fn strip_word_prefix<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let rest = text.strip_prefix(prefix)?;
+    if prefix.ends_with('.') || rest.starts_with(char::is_whitespace) {
+        Some(rest.trim_start())
+    } else {
+        None
+    }
+}
+
+// Toàn chữ thường hoặc toàn chữ hoa (có ít nhất một chữ cái)
+fn is_single_case(text: &str) -> bool {
+    let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+    !letters.is_empty()
+        && (letters.iter().all(|c| c.is_lowercase()) || letters.iter().all(|c| c.is_uppercase()))
+}
+
+fn title_case(text: &str) -> String {
+    text.split(' ')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars.flat_map(|c| c.to_lowercase())).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn write_default(path: &Path) {
+    if let Some(dir) = path.parent() {
+        if !dir.exists() {
+            if let Err(e) = fs::create_dir_all(dir) {
+                println!("Error creating address rules directory: {}", e);
+                return;
+            }
+        }
+    }
+
+    match fs::write(path, DEFAULT_RULES_JSON) {
+        Ok(_) => println!("Default address rules written to: {:?}", path),
+        Err(e) => println!("Error writing default address rules: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(admin_prefixes: &[(&str, &[&str])], abbreviations: &[(&str, &[&str])]) -> AddressNormalizer {
+        let to_strings = |items: &[&str]| items.iter().map(|s| s.to_string()).collect();
+        AddressNormalizer::new(AddressRulesFile {
+            version: 1,
+            street_prefixes: Vec::new(),
+            admin_prefixes: admin_prefixes
+                .iter()
+                .map(|(canonical, aliases)| PrefixRule {
+                    canonical: canonical.to_string(),
+                    aliases: to_strings(aliases),
+                })
+                .collect(),
+            abbreviations: abbreviations
+                .iter()
+                .map(|(to, from)| Abbreviation {
+                    to: to.to_string(),
+                    from: to_strings(from),
+                })
+                .collect(),
+            title_case: false,
+        })
+    }
+
+    // Thư mục tạm riêng cho từng test
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("address_rules_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn expands_admin_prefixes() {
+        let normalizer = AddressNormalizer::builtin();
+
+        assert_eq!(normalizer.part("P.12"), "Phường 12");
+        assert_eq!(normalizer.part("Q1"), "Quận 1");
+        assert_eq!(normalizer.part("TX. Thuận An"), "Thị xã Thuận An");
+        // Đã ở dạng chuẩn: "P" không khớp vì theo sau là chữ
+        assert_eq!(normalizer.part("Phường Bến Nghé"), "Phường Bến Nghé");
+    }
+
+    #[test]
+    fn expands_abbreviations_as_whole_words() {
+        let normalizer = AddressNormalizer::builtin();
+
+        assert_eq!(normalizer.part("TP.HCM"), "Thành phố Hồ Chí Minh");
+        assert_eq!(normalizer.part("Viet Nam"), "Việt Nam");
+        // Không thay khi chỉ là một phần của từ
+        assert_eq!(normalizer.part("Trường HCMUT"), "Trường HCMUT");
+    }
+
+    #[test]
+    fn title_cases_before_matching_rules() {
+        let normalizer = AddressNormalizer::builtin();
+
+        assert_eq!(normalizer.part("tp hcm"), "Thành phố Hồ Chí Minh");
+        assert_eq!(normalizer.street_name("đường lê lợi"), "Lê Lợi");
+        assert_eq!(
+            normalizer.address("12 đường Lê Lợi,  P.Bến Nghé, Q.1, tp hcm"),
+            "12 đường Lê Lợi, Phường Bến Nghé, Quận 1, Thành phố Hồ Chí Minh"
+        );
+    }
+
+    #[test]
+    fn longer_alias_wins_regardless_of_rule_order() {
+        // "T." khai báo trước nhưng "TP." dài hơn nên được thử trước
+        let normalizer = rules(&[("Tỉnh", &["T."]), ("Thành phố", &["TP."])], &[]);
+
+        assert_eq!(normalizer.part("TP. Huế"), "Thành phố Huế");
+        assert_eq!(normalizer.part("T. Bình Dương"), "Tỉnh Bình Dương");
+    }
+
+    #[test]
+    fn abbreviations_apply_in_file_order() {
+        // Rule đầu đã thay "HN" nên rule sau không thấy "HN" nữa
+        let normalizer = rules(&[], &[("Hà Nội", &["HN"]), ("Hoàn Kiếm", &["HN", "HK"])]);
+
+        assert_eq!(normalizer.part("HN"), "Hà Nội");
+        assert_eq!(normalizer.part("Q. HK"), "Q. Hoàn Kiếm");
+    }
+
+    #[test]
+    fn missing_rules_file_writes_default() {
+        let dir = temp_dir("missing");
+        let path = dir.join("address_rules.json");
+
+        let normalizer = AddressNormalizer::load(Some(path.clone()));
+
+        assert_eq!(normalizer.part("P.12"), "Phường 12");
+        assert_eq!(fs::read_to_string(&path).unwrap(), DEFAULT_RULES_JSON);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn invalid_rules_file_falls_back_to_builtin() {
+        let dir = temp_dir("invalid");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("address_rules.json");
+        fs::write(&path, "{ \"version\": 2, \"admin_prefixes\": [").unwrap();
+
+        let normalizer = AddressNormalizer::load(Some(path.clone()));
+
+        assert_eq!(normalizer.rules.version, 1);
+        assert_eq!(normalizer.part("Q1"), "Quận 1");
+        // File lỗi được giữ nguyên để người dùng sửa
+        assert!(fs::read_to_string(&path).unwrap().starts_with("{ \"version\": 2"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::ExampleResult;
+
+// Tiền tố (đã chuẩn hóa) của từng cấp hành chính
+const WARD_PREFIXES: &[&str] = &["Phường", "Xã", "Thị trấn"];
+const DISTRICT_PREFIXES: &[&str] = &["Quận", "Huyện", "Thị xã", "Thành phố"];
+const PROVINCE_PREFIXES: &[&str] = &["Tỉnh", "Thành phố"];
+const COUNTRY_NAMES: &[&str] = &["Việt Nam", "Vietnam", "Viet Nam"];
+
+// Quận/huyện và danh sách phường/xã trực thuộc
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct District {
+    pub name: String,
+    #[serde(default)]
+    pub wards: Vec<String>,
+}
+
+// Tỉnh/thành phố và danh sách quận/huyện trực thuộc
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Province {
+    pub name: String,
+    #[serde(default)]
+    pub districts: Vec<District>,
+}
+
+// Danh mục đơn vị hành chính offline (admin_units.json), dùng để suy ra cấp trên
+// khi upstream chỉ trả về cấp dưới (vd. có phường nhưng thiếu quận/tỉnh)
+#[derive(Debug, Clone, Default)]
+pub struct AdminUnits {
+    provinces: Vec<Province>,
+}
+
+impl AdminUnits {
+    // Load từ admin_units.json, không có file thì danh mục rỗng
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let Some(path) = path.filter(|p| p.exists()) else {
+            println!("Admin units file not found, offline lookup disabled");
+            return Self::default();
+        };
+
+        let loaded = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<Vec<Province>>(&content).map_err(|e| e.to_string()));
+
+        match loaded {
+            Ok(provinces) => {
+                println!("Loaded {} provinces from {:?}", provinces.len(), path);
+                Self { provinces }
+            }
+            Err(e) => {
+                println!("Error loading admin units: {}, offline lookup disabled", e);
+                Self::default()
+            }
+        }
+    }
+
+    // Điền province/district/ward còn thiếu: trước hết từ các phần của address,
+    // sau đó tra danh mục offline theo cấp dưới đã biết
+    pub fn fill_missing(&self, result: &mut ExampleResult) {
+        fill_from_address(result);
+
+        if result.ward.is_some() && (result.district.is_none() || result.province.is_none()) {
+            if let Some((province, district)) = self.find_ward(result) {
+                result.province.get_or_insert(province);
+                result.district.get_or_insert(district);
+            }
+        }
+
+        if result.district.is_some() && result.province.is_none() {
+            if let Some(province) = self.find_district(result) {
+                result.province = Some(province);
+            }
+        }
+    }
+
+    // Tìm (tỉnh, quận) chứa phường của result; chỉ trả về khi kết quả là duy nhất
+    fn find_ward(&self, result: &ExampleResult) -> Option<(String, String)> {
+        let ward = name_key(result.ward.as_deref()?);
+        let district = result.district.as_deref().map(name_key);
+        let province = result.province.as_deref().map(name_key);
+
+        let matches: Vec<(&Province, &District)> = self
+            .provinces
+            .iter()
+            .filter(|p| province.as_ref().map_or(true, |k| name_key(&p.name) == *k))
+            .flat_map(|p| p.districts.iter().map(move |d| (p, d)))
+            .filter(|(_, d)| district.as_ref().map_or(true, |k| name_key(&d.name) == *k))
+            .filter(|(_, d)| d.wards.iter().any(|w| name_key(w) == ward))
+            .collect();
+
+        match matches.as_slice() {
+            [(p, d)] => Some((p.name.clone(), d.name.clone())),
+            _ => None,
+        }
+    }
+
+    // Tìm tỉnh chứa quận của result; chỉ trả về khi kết quả là duy nhất
+    fn find_district(&self, result: &ExampleResult) -> Option<String> {
+        let district = name_key(result.district.as_deref()?);
+
+        let matches: Vec<&Province> = self
+            .provinces
+            .iter()
+            .filter(|p| p.districts.iter().any(|d| name_key(&d.name) == district))
+            .collect();
+
+        match matches.as_slice() {
+            [p] => Some(p.name.clone()),
+            _ => None,
+        }
+    }
+}
+
+// Đọc các cấp hành chính từ cuối address: "..., Phường X, Quận Y, Thành phố Z, Việt Nam"
+fn fill_from_address(result: &mut ExampleResult) {
+    let mut parts: Vec<&str> = result
+        .address
+        .split(',')
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .filter(|p| !COUNTRY_NAMES.contains(p))
+        // Bỏ mã bưu chính ("700000")
+        .filter(|p| !p.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+
+    if result.province.is_none() {
+        if let Some(last) = parts.last().filter(|p| has_prefix(p, PROVINCE_PREFIXES)) {
+            result.province = Some(last.to_string());
+        }
+    }
+    if result.province.as_deref().is_some_and(|p| parts.last().is_some_and(|last| name_key(last) == name_key(p))) {
+        parts.pop();
+    }
+
+    if result.district.is_none() {
+        if let Some(last) = parts.last().filter(|p| has_prefix(p, DISTRICT_PREFIXES)) {
+            result.district = Some(last.to_string());
+        }
+    }
+    if result.district.as_deref().is_some_and(|d| parts.last().is_some_and(|last| name_key(last) == name_key(d))) {
+        parts.pop();
+    }
+
+    if result.ward.is_none() {
+        if let Some(last) = parts.last().filter(|p| has_prefix(p, WARD_PREFIXES)) {
+            result.ward = Some(last.to_string());
+        }
+    }
+}
+
+fn has_prefix(text: &str, prefixes: &[&str]) -> bool {
+    prefixes
+        .iter()
+        .any(|prefix| text.strip_prefix(prefix).is_some_and(|rest| rest.starts_with(' ')))
+}
+
+// Key so sánh tên: NFC, chữ thường, bỏ tiền tố hành chính ("Phường Bến Nghé" ~ "Bến Nghé")
+fn name_key(name: &str) -> String {
+    let name: String = name.trim().nfc().collect();
+
+    let stripped = WARD_PREFIXES
+        .iter()
+        .chain(DISTRICT_PREFIXES)
+        .chain(PROVINCE_PREFIXES)
+        .find_map(|prefix| name.strip_prefix(prefix).and_then(|rest| rest.strip_prefix(' ')))
+        .unwrap_or(&name);
+
+    stripped.to_lowercase()
+}
@@ -0,0 +1,353 @@
+use geojson::{GeoJson, Value as GeometryValue};
+use rstar::{RTree, RTreeObject, AABB};
+use serde::{Deserialize, Serialize};
+use shapefile::dbase::FieldValue;
+use shapefile::record::traits::HasXY;
+use shapefile::{PolygonRing, Shape};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Tên thuộc tính (properties) chứa tên phường/quận/tỉnh trong file ranh giới
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoundaryProperties {
+    #[serde(default = "default_ward_property")]
+    pub ward: String,
+    #[serde(default = "default_district_property")]
+    pub district: String,
+    #[serde(default = "default_province_property")]
+    pub province: String,
+}
+
+fn default_ward_property() -> String {
+    "ward".to_string()
+}
+
+fn default_district_property() -> String {
+    "district".to_string()
+}
+
+fn default_province_property() -> String {
+    "province".to_string()
+}
+
+impl Default for BoundaryProperties {
+    fn default() -> Self {
+        Self {
+            ward: default_ward_property(),
+            district: default_district_property(),
+            province: default_province_property(),
+        }
+    }
+}
+
+// Polygon theo tọa độ [lng, lat]: vòng ngoài và các lỗ
+struct Polygon {
+    exterior: Vec<[f64; 2]>,
+    holes: Vec<Vec<[f64; 2]>>,
+}
+
+impl Polygon {
+    fn contains(&self, point: [f64; 2]) -> bool {
+        ring_contains(&self.exterior, point) && !self.holes.iter().any(|hole| ring_contains(hole, point))
+    }
+}
+
+// Một đơn vị hành chính (một feature) trong file ranh giới
+pub struct BoundaryArea {
+    polygons: Vec<Polygon>,
+    envelope: AABB<[f64; 2]>,
+    pub ward: Option<String>,
+    pub district: Option<String>,
+    pub province: Option<String>,
+}
+
+impl RTreeObject for BoundaryArea {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+impl BoundaryArea {
+    fn area(&self) -> f64 {
+        let (lower, upper) = (self.envelope.lower(), self.envelope.upper());
+        (upper[0] - lower[0]) * (upper[1] - lower[1])
+    }
+}
+
+// Chỉ mục R-tree các polygon ranh giới để tra phường/quận/tỉnh theo tọa độ
+#[derive(Default)]
+pub struct BoundaryIndex {
+    tree: RTree<BoundaryArea>,
+}
+
+impl BoundaryIndex {
+    // Load file GeoJSON (FeatureCollection Polygon/MultiPolygon) hoặc Shapefile (.shp kèm .dbf),
+    // lỗi thì trả về chỉ mục rỗng
+    pub fn load(path: &Path, properties: &BoundaryProperties) -> Self {
+        if !path.exists() {
+            println!("Boundary file not found: {:?}, boundary lookup disabled", path);
+            return Self::default();
+        }
+
+        let areas = match shapefile_path(path) {
+            Some(shp_path) => read_shapefile(&shp_path, properties),
+            None => read_geojson(path, properties),
+        };
+
+        let areas = match areas {
+            Ok(areas) => areas,
+            Err(e) => {
+                println!("Error loading boundary file {:?}: {}", path, e);
+                return Self::default();
+            }
+        };
+
+        println!("Loaded {} boundary areas from {:?}", areas.len(), path);
+
+        Self {
+            tree: RTree::bulk_load(areas),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.size() == 0
+    }
+
+    // Tìm đơn vị chứa điểm; nếu có nhiều (các lớp chồng nhau) thì lấy đơn vị nhỏ nhất
+    pub fn lookup(&self, lat: f64, lng: f64) -> Option<&BoundaryArea> {
+        let point = [lng, lat];
+
+        self.tree
+            .locate_in_envelope_intersecting(&AABB::from_point(point))
+            .filter(|area| area.polygons.iter().any(|polygon| polygon.contains(point)))
+            .min_by(|a, b| a.area().total_cmp(&b.area()))
+    }
+}
+
+fn read_geojson(path: &Path, properties: &BoundaryProperties) -> Result<Vec<BoundaryArea>, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let features = match content.parse::<GeoJson>().map_err(|e| e.to_string())? {
+        GeoJson::FeatureCollection(collection) => collection.features,
+        GeoJson::Feature(feature) => vec![feature],
+        GeoJson::Geometry(_) => return Err("file has no features".to_string()),
+    };
+
+    Ok(features
+        .iter()
+        .filter_map(|feature| {
+            let polygons = match &feature.geometry.as_ref()?.value {
+                GeometryValue::Polygon(rings) => vec![to_polygon(rings)?],
+                GeometryValue::MultiPolygon(parts) => parts.iter().filter_map(|rings| to_polygon(rings)).collect(),
+                _ => return None,
+            };
+
+            let envelope = envelope_of(&polygons)?;
+            let property = |name: &str| {
+                feature.property(name).and_then(|value| match value {
+                    serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+                    serde_json::Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                })
+            };
+
+            Some(BoundaryArea {
+                polygons,
+                envelope,
+                ward: property(&properties.ward),
+                district: property(&properties.district),
+                province: property(&properties.province),
+            })
+        })
+        .collect())
+}
+
+// Đường dẫn .shp nếu file ranh giới là Shapefile (chọn .shp hoặc .dbf đi kèm)
+fn shapefile_path(path: &Path) -> Option<PathBuf> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "shp" => Some(path.to_path_buf()),
+        "dbf" => Some(path.with_extension("shp")),
+        _ => None,
+    }
+}
+
+// Đọc Shapefile: hình từ .shp, tên phường/quận/tỉnh từ .dbf cùng tên.
+// Tọa độ phải là kinh độ/vĩ độ WGS84, file VN-2000 cần chuyển hệ tọa độ trước
+fn read_shapefile(path: &Path, properties: &BoundaryProperties) -> Result<Vec<BoundaryArea>, String> {
+    let records = shapefile::read(path).map_err(|e| e.to_string())?;
+
+    let areas: Vec<BoundaryArea> = records
+        .iter()
+        .filter_map(|(shape, record)| {
+            let polygons = match shape {
+                Shape::Polygon(polygon) => shp_polygons(polygon.rings()),
+                Shape::PolygonM(polygon) => shp_polygons(polygon.rings()),
+                Shape::PolygonZ(polygon) => shp_polygons(polygon.rings()),
+                _ => return None,
+            };
+
+            let envelope = envelope_of(&polygons)?;
+            let property = |name: &str| {
+                record.get(name).and_then(|value| match value {
+                    FieldValue::Character(Some(s)) if !s.trim().is_empty() => Some(s.trim().to_string()),
+                    FieldValue::Numeric(Some(n)) => Some(n.to_string()),
+                    FieldValue::Integer(n) => Some(n.to_string()),
+                    _ => None,
+                })
+            };
+
+            Some(BoundaryArea {
+                polygons,
+                envelope,
+                ward: property(&properties.ward),
+                district: property(&properties.district),
+                province: property(&properties.province),
+            })
+        })
+        .collect();
+
+    let out_of_range = |area: &BoundaryArea| {
+        let (lower, upper) = (area.envelope.lower(), area.envelope.upper());
+        lower[0] < -180.0 || upper[0] > 180.0 || lower[1] < -90.0 || upper[1] > 90.0
+    };
+    if areas.iter().any(out_of_range) {
+        return Err("coordinates are not WGS84 longitude/latitude, reproject the Shapefile to EPSG:4326".to_string());
+    }
+
+    Ok(areas)
+}
+
+// Vòng Shapefile theo thứ tự: vòng ngoài rồi tới các lỗ của nó
+fn shp_polygons<P: HasXY>(rings: &[PolygonRing<P>]) -> Vec<Polygon> {
+    let mut polygons: Vec<Polygon> = Vec::new();
+
+    for ring in rings {
+        let points: Vec<[f64; 2]> = ring.points().iter().map(|p| [p.x(), p.y()]).collect();
+        if points.len() < 3 {
+            continue;
+        }
+
+        match ring {
+            PolygonRing::Outer(_) => polygons.push(Polygon {
+                exterior: points,
+                holes: Vec::new(),
+            }),
+            PolygonRing::Inner(_) => {
+                if let Some(polygon) = polygons.last_mut() {
+                    polygon.holes.push(points);
+                }
+            }
+        }
+    }
+
+    polygons
+}
+
+fn to_polygon(rings: &[Vec<Vec<f64>>]) -> Option<Polygon> {
+    let mut rings = rings.iter().map(|ring| {
+        ring.iter()
+            .filter(|position| position.len() >= 2)
+            .map(|position| [position[0], position[1]])
+            .collect::<Vec<_>>()
+    });
+
+    let exterior = rings.next().filter(|ring| ring.len() >= 3)?;
+    Some(Polygon {
+        exterior,
+        holes: rings.filter(|ring| ring.len() >= 3).collect(),
+    })
+}
+
+fn envelope_of(polygons: &[Polygon]) -> Option<AABB<[f64; 2]>> {
+    let points: Vec<[f64; 2]> = polygons.iter().flat_map(|p| p.exterior.iter().copied()).collect();
+    if points.is_empty() {
+        return None;
+    }
+    Some(AABB::from_points(points.iter()))
+}
+
+// Ray casting: đếm số cạnh của vòng cắt tia ngang từ điểm sang phải
+fn ring_contains(ring: &[[f64; 2]], point: [f64; 2]) -> bool {
+    let [x, y] = point;
+    let mut inside = false;
+    let mut j = ring.len() - 1;
+
+    for i in 0..ring.len() {
+        let [xi, yi] = ring[i];
+        let [xj, yj] = ring[j];
+
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shapefile::dbase::{Record, TableWriterBuilder};
+    use shapefile::{Point, Writer};
+
+    fn square(x0: f64, y0: f64, size: f64) -> Vec<Point> {
+        vec![
+            Point::new(x0, y0),
+            Point::new(x0, y0 + size),
+            Point::new(x0 + size, y0 + size),
+            Point::new(x0 + size, y0),
+            Point::new(x0, y0),
+        ]
+    }
+
+    #[test]
+    fn loads_shapefile_with_dbf_attributes() {
+        let dir = std::env::temp_dir().join(format!("boundary_shp_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("wards.shp");
+
+        let table = TableWriterBuilder::new()
+            .add_character_field("TEN_PHUONG".try_into().unwrap(), 50)
+            .add_character_field("TEN_QUAN".try_into().unwrap(), 50)
+            .add_numeric_field("MA_TINH".try_into().unwrap(), 10, 0);
+        {
+            let mut writer = Writer::from_path(&path, table).unwrap();
+
+            // Phường có một lỗ ở giữa
+            let ward = shapefile::Polygon::with_rings(vec![
+                PolygonRing::Outer(square(106.70, 10.77, 0.02)),
+                PolygonRing::Inner(square(106.705, 10.775, 0.005)),
+            ]);
+            let mut record = Record::default();
+            record.insert("TEN_PHUONG".to_string(), FieldValue::Character(Some("Bến Nghé".to_string())));
+            record.insert("TEN_QUAN".to_string(), FieldValue::Character(Some("Quận 1".to_string())));
+            record.insert("MA_TINH".to_string(), FieldValue::Numeric(Some(79.0)));
+            writer.write_shape_and_record(&ward, &record).unwrap();
+        }
+
+        let properties = BoundaryProperties {
+            ward: "TEN_PHUONG".to_string(),
+            district: "TEN_QUAN".to_string(),
+            province: "MA_TINH".to_string(),
+        };
+
+        for file in [path.clone(), path.with_extension("dbf")] {
+            let index = BoundaryIndex::load(&file, &properties);
+
+            let area = index.lookup(10.772, 106.715).expect("point inside ward");
+            assert_eq!(area.ward.as_deref(), Some("Bến Nghé"));
+            assert_eq!(area.district.as_deref(), Some("Quận 1"));
+            assert_eq!(area.province.as_deref(), Some("79"));
+
+            // Trong lỗ và ngoài ranh giới
+            assert!(index.lookup(10.777, 106.707).is_none());
+            assert!(index.lookup(10.80, 106.80).is_none());
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,286 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::type_mapping::TypeMapping;
+use crate::ExampleResult;
+
+// Danh mục thương hiệu mặc định đi kèm app, được ghi ra thư mục config ở lần chạy đầu
+const DEFAULT_BRANDS_JSON: &str = include_str!("../resources/brands.json");
+
+// Ký tự nối giữa tên thương hiệu và tên chi nhánh ("Highlands Coffee - Vincom Đồng Khởi")
+const SUFFIX_SEPARATORS: &[char] = &['-', '–', '—', ':', '|', ',', '(', ')'];
+
+// Một thương hiệu: tên chuẩn, các cách viết trong tên POI và sub_type mặc định (nếu có)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Brand {
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub sub_type: Option<String>,
+}
+
+// Format file brands.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BrandsFile {
+    version: u32,
+    #[serde(default)]
+    brands: Vec<Brand>,
+}
+
+// Một từ của tên POI: vị trí kết thúc trong chuỗi gốc và key so sánh (bỏ dấu, chữ thường)
+struct Token {
+    end: usize,
+    key: String,
+}
+
+// Thương hiệu tìm thấy ở đầu tên: chỉ số brand và số từ [0, end) khớp alias
+struct BrandMatch {
+    brand: usize,
+    end: usize,
+}
+
+// Nhận diện thương hiệu chuỗi (Circle K, Vietcombank, ...) từ poi_vn/poi_en
+pub struct BrandDictionary {
+    brands: Vec<Brand>,
+    // (key các từ của alias, chỉ số brand), alias nhiều từ xếp trước
+    aliases: Vec<(Vec<String>, usize)>,
+}
+
+impl BrandDictionary {
+    pub fn builtin() -> Self {
+        let file: BrandsFile = serde_json::from_str(DEFAULT_BRANDS_JSON).expect("built-in brands.json is invalid");
+        Self::new(file.brands)
+    }
+
+    // Load từ brands.json, chưa có thì ghi danh mục mặc định ra, lỗi thì dùng mặc định
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let Some(path) = path else {
+            return Self::builtin();
+        };
+
+        if !path.exists() {
+            write_default(&path);
+            return Self::builtin();
+        }
+
+        let loaded = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<BrandsFile>(&content).map_err(|e| e.to_string()));
+
+        match loaded {
+            Ok(file) => {
+                println!("Loaded {} brands (v{}) from {:?}", file.brands.len(), file.version, path);
+                Self::new(file.brands)
+            }
+            Err(e) => {
+                println!("Error loading brands: {}, using built-in brands", e);
+                Self::builtin()
+            }
+        }
+    }
+
+    fn new(brands: Vec<Brand>) -> Self {
+        let mut aliases: Vec<(Vec<String>, usize)> = brands
+            .iter()
+            .enumerate()
+            .flat_map(|(index, brand)| {
+                brand
+                    .aliases
+                    .iter()
+                    .chain(std::iter::once(&brand.name))
+                    .map(move |alias| (tokenize(alias).into_iter().map(|t| t.key).collect::<Vec<_>>(), index))
+            })
+            .filter(|(keys, _)| !keys.is_empty())
+            .collect();
+        aliases.sort_by_key(|(keys, _)| std::cmp::Reverse(keys.len()));
+
+        Self { brands, aliases }
+    }
+
+    // Điền brandname (và sub_type mặc định nếu còn trống). Khi tên bắt đầu bằng thương hiệu,
+    // phần tên chi nhánh phía sau được tách sang poi_ex
+    pub fn apply(&self, result: &mut ExampleResult, mapping: &TypeMapping) {
+        if result.brandname.is_some() {
+            return;
+        }
+
+        let found = [result.poi_vn.as_deref(), result.poi_en.as_deref()]
+            .into_iter()
+            .flatten()
+            .find_map(|name| self.find(name).map(|m| m.brand));
+        let Some(index) = found else {
+            return;
+        };
+        let brand = &self.brands[index];
+
+        result.brandname = Some(brand.name.clone());
+
+        for name in [&mut result.poi_vn, &mut result.poi_en] {
+            let Some((head, suffix)) = name.as_deref().and_then(|n| self.split_branch(n, index)) else {
+                continue;
+            };
+            *name = Some(head);
+            if result.poi_ex.is_none() {
+                result.poi_ex = Some(suffix);
+            }
+        }
+
+        if let Some(sub_type) = &brand.sub_type {
+            let category = mapping.category_of(sub_type);
+            let same_category = result.r#type.is_none() || result.r#type == category;
+            if result.sub_type.is_none() && same_category {
+                result.r#type = category;
+                result.sub_type = Some(sub_type.clone());
+            }
+        }
+    }
+
+    // Tìm alias dài nhất (theo số từ) ở đầu tên. Alias giữa tên không tính vì hay là tên
+    // đường/địa danh ("Hẻm Long Châu", "Cà phê ở đường Trung Nguyên"); cách gọi có tiền tố
+    // ("Nhà thuốc Long Châu") được khai báo thành alias riêng trong brands.json
+    fn find(&self, name: &str) -> Option<BrandMatch> {
+        let keys: Vec<String> = tokenize(name).into_iter().map(|t| t.key).collect();
+        self.find_in(&keys, None)
+    }
+
+    fn find_in(&self, keys: &[String], only_brand: Option<usize>) -> Option<BrandMatch> {
+        self.aliases
+            .iter()
+            .filter(|(_, brand)| only_brand.map_or(true, |only| only == *brand))
+            .find(|(alias, _)| keys.starts_with(alias))
+            .map(|(alias, brand)| BrandMatch {
+                brand: *brand,
+                end: alias.len(),
+            })
+    }
+
+    // "Highlands Coffee - Vincom Đồng Khởi" -> ("Highlands Coffee", "Vincom Đồng Khởi").
+    // Chỉ tách khi còn phần phía sau tên thương hiệu
+    fn split_branch(&self, name: &str, brand: usize) -> Option<(String, String)> {
+        let tokens = tokenize(name);
+        let keys: Vec<String> = tokens.iter().map(|t| t.key.clone()).collect();
+
+        let found = self.find_in(&keys, Some(brand))?;
+        if found.end >= tokens.len() {
+            return None;
+        }
+
+        let split_at = tokens[found.end - 1].end;
+        let head = name[..split_at].trim();
+        let suffix = name[split_at..]
+            .trim_matches(|c: char| c.is_whitespace() || SUFFIX_SEPARATORS.contains(&c) || c == '+')
+            .to_string();
+
+        if suffix.is_empty() {
+            return None;
+        }
+
+        // Giữ dấu "+" của tên thương hiệu ("WinMart+")
+        let head = match name[split_at..].trim_start().strip_prefix('+') {
+            Some(_) => format!("{}+", head),
+            None => head.to_string(),
+        };
+
+        Some((head, suffix))
+    }
+}
+
+// Tách tên thành các từ (chuỗi chữ/số liên tiếp) kèm key đã bỏ dấu, chữ thường
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start: Option<usize> = None;
+
+    for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                tokens.push(Token {
+                    end: i,
+                    key: fold(&text[s..i]),
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+// Key so sánh: bỏ dấu tiếng Việt ("Bách Hóa" ~ "Bach Hoa"), đ -> d, chữ thường
+pub fn fold(word: &str) -> String {
+    word.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(|c| c.to_lowercase())
+        .map(|c| if c == 'đ' { 'd' } else { c })
+        .collect()
+}
+
+fn write_default(path: &Path) {
+    if let Some(dir) = path.parent() {
+        if !dir.exists() {
+            if let Err(e) = fs::create_dir_all(dir) {
+                println!("Error creating brands directory: {}", e);
+                return;
+            }
+        }
+    }
+
+    match fs::write(path, DEFAULT_BRANDS_JSON) {
+        Ok(_) => println!("Default brands written to: {:?}", path),
+        Err(e) => println!("Error writing default brands: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brand_of(name: &str) -> Option<String> {
+        let dictionary = BrandDictionary::builtin();
+        dictionary.find(name).map(|m| dictionary.brands[m.brand].name.clone())
+    }
+
+    #[test]
+    fn matches_alias_at_start_of_name() {
+        assert_eq!(brand_of("Highlands Coffee - Vincom Đồng Khởi").as_deref(), Some("Highlands Coffee"));
+        assert_eq!(brand_of("nha thuoc long chau 123").as_deref(), Some("Long Châu"));
+        assert_eq!(brand_of("Trung Nguyên Legend Café").as_deref(), Some("Trung Nguyên Legend"));
+    }
+
+    #[test]
+    fn ignores_alias_inside_name() {
+        assert_eq!(brand_of("Cà phê ở đường Trung Nguyên"), None);
+        assert_eq!(brand_of("Hẻm Long Châu"), None);
+        assert_eq!(brand_of("Chung cư 12 đường Highlands Park"), None);
+    }
+
+    #[test]
+    fn splits_branch_name_into_poi_ex() {
+        let mut result = ExampleResult {
+            poi_vn: Some("Highlands Coffee - Vincom Đồng Khởi".to_string()),
+            ..Default::default()
+        };
+        BrandDictionary::builtin().apply(&mut result, &TypeMapping::builtin());
+
+        assert_eq!(result.brandname.as_deref(), Some("Highlands Coffee"));
+        assert_eq!(result.poi_vn.as_deref(), Some("Highlands Coffee"));
+        assert_eq!(result.poi_ex.as_deref(), Some("Vincom Đồng Khởi"));
+    }
+
+    #[test]
+    fn leaves_street_name_without_brand() {
+        let mut result = ExampleResult {
+            poi_vn: Some("Hẻm Long Châu".to_string()),
+            ..Default::default()
+        };
+        BrandDictionary::builtin().apply(&mut result, &TypeMapping::builtin());
+
+        assert_eq!(result.brandname, None);
+        assert_eq!(result.poi_vn.as_deref(), Some("Hẻm Long Châu"));
+    }
+}
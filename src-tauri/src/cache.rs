@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+// Số chữ số thập phân khi làm tròn lat/lng cho key (5 chữ số ~ 1.1m)
+const COORD_DECIMALS: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    value: Value,
+    // Thời điểm lưu (unix timestamp, giây)
+    stored_at: i64,
+}
+
+// Cache lưu response gốc của /geocode và /placedetails xuống file cache.json
+pub struct ResponseCache {
+    path: Option<PathBuf>,
+    entries: Mutex<HashMap<String, CacheEntry>>,
+    // Có thay đổi chưa ghi xuống file (ghi định kỳ bằng flush, không ghi trong request)
+    dirty: AtomicBool,
+}
+
+// Key có prefix theo provider để không lẫn response giữa các provider
+pub fn geocode_key(provider: &str, lat: f64, lng: f64) -> String {
+    format!("{}:geocode:{:.*},{:.*}", provider, COORD_DECIMALS, lat, COORD_DECIMALS, lng)
+}
+
+pub fn placedetails_key(provider: &str, place_id: &str) -> String {
+    format!("{}:placedetails:{}", provider, place_id)
+}
+
+// Place details theo ngôn ngữ khác mặc định (vd. "en" cho poi_en)
+pub fn placedetails_lang_key(provider: &str, place_id: &str, language: &str) -> String {
+    format!("{}:placedetails:{}:{}", provider, place_id, language)
+}
+
+pub fn nearby_key(provider: &str, lat: f64, lng: f64, radius_m: u32) -> String {
+    format!("{}:nearby:{:.*},{:.*}:{}", provider, COORD_DECIMALS, lat, COORD_DECIMALS, lng, radius_m)
+}
+
+// Chuẩn hóa query (trim, lowercase) để các lần tìm giống nhau dùng chung cache
+pub fn search_key(provider: &str, query: &str) -> String {
+    format!("{}:search:{}", provider, query.trim().to_lowercase())
+}
+
+// Email tìm được trên website, theo domain (không phụ thuộc provider)
+pub fn contact_key(domain: &str) -> String {
+    format!("contact:email:{}", domain)
+}
+
+// Chỉ cache các response hợp lệ, không cache lỗi tạm thời (OVER_QUERY_LIMIT, ...)
+pub fn is_cacheable(response: &Value) -> bool {
+    matches!(response["status"].as_str(), Some("OK") | Some("ZERO_RESULTS"))
+}
+
+impl ResponseCache {
+    // Load cache từ file, nếu lỗi thì bắt đầu với cache rỗng
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let entries = path
+            .as_ref()
+            .filter(|p| p.exists())
+            .and_then(|p| match fs::read_to_string(p) {
+                Ok(content) => match serde_json::from_str(&content) {
+                    Ok(entries) => Some(entries),
+                    Err(e) => {
+                        println!("Error parsing cache file: {}, starting empty", e);
+                        None
+                    }
+                },
+                Err(e) => {
+                    println!("Error reading cache file: {}, starting empty", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        Self {
+            path,
+            entries: Mutex::new(entries),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    // Lấy giá trị nếu còn hạn (ttl_hours = 0 nghĩa là không hết hạn)
+    pub fn get(&self, key: &str, ttl_hours: u64) -> Option<Value> {
+        let entries = self.entries.lock().ok()?;
+        let entry = entries.get(key)?;
+
+        if ttl_hours > 0 {
+            let age = chrono::Utc::now().timestamp() - entry.stored_at;
+            if age > (ttl_hours * 3600) as i64 {
+                return None;
+            }
+        }
+
+        Some(entry.value.clone())
+    }
+
+    // Thêm giá trị, xóa các entry cũ nhất nếu vượt quá max_entries
+    pub fn insert(&self, key: String, value: Value, max_entries: usize) {
+        if max_entries == 0 {
+            return;
+        }
+
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(
+                key,
+                CacheEntry {
+                    value,
+                    stored_at: chrono::Utc::now().timestamp(),
+                },
+            );
+
+            if entries.len() > max_entries {
+                let mut by_age: Vec<(String, i64)> = entries
+                    .iter()
+                    .map(|(k, e)| (k.clone(), e.stored_at))
+                    .collect();
+                by_age.sort_by_key(|(_, stored_at)| *stored_at);
+
+                let overflow = entries.len() - max_entries;
+                for (k, _) in by_age.into_iter().take(overflow) {
+                    entries.remove(&k);
+                }
+            }
+
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        self.flush();
+        println!("Response cache cleared");
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().map(|e| e.len()).unwrap_or(0)
+    }
+
+    // Ghi cache.json nếu có thay đổi. Chỉ giữ lock lúc serialize, ghi file sau khi nhả lock
+    pub fn flush(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return;
+        }
+
+        let json = match self.entries.lock() {
+            Ok(entries) => serde_json::to_string(&*entries),
+            Err(_) => return,
+        };
+
+        if let Some(dir) = path.parent() {
+            if !dir.exists() {
+                if let Err(e) = fs::create_dir_all(dir) {
+                    println!("Error creating cache directory: {}", e);
+                    return;
+                }
+            }
+        }
+
+        match json {
+            Ok(json) => {
+                if let Err(e) = fs::write(path, json) {
+                    println!("Error writing cache file: {}", e);
+                    self.dirty.store(true, Ordering::Relaxed);
+                }
+            }
+            Err(e) => println!("Error serializing cache: {}", e),
+        }
+    }
+}
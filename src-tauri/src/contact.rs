@@ -0,0 +1,268 @@
+use reqwest::Url;
+use std::time::Duration;
+
+use crate::upstream::RetryPolicy;
+use crate::{append_note, ExampleResult};
+
+// Tham số query dùng để tracking, bị bỏ khỏi URL website
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid", "gclid", "gbraid", "wbraid", "dclid", "msclkid", "yclid", "igshid",
+    "mc_cid", "mc_eid", "_ga", "_gl", "zarsrc", "ref", "ref_src",
+];
+
+// Domain mạng xã hội: link dạng này được ghi vào social thay vì web
+const SOCIAL_DOMAINS: &[&str] = &[
+    "facebook.com", "fb.com", "fb.me", "zalo.me", "instagram.com", "tiktok.com",
+    "youtube.com", "youtu.be", "twitter.com", "x.com", "linkedin.com",
+];
+
+// Trang thử lần lượt khi tìm email trên website (trang chủ trước)
+const CONTACT_PATHS: &[&str] = &["", "/lien-he", "/contact", "/contact-us"];
+
+// Phần mở rộng file ảnh hay bị nhận nhầm là email ("logo@2x.png")
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg"];
+
+// Chuẩn hóa web/mail của result: URL gọn (https, bỏ tracking), link mạng xã hội chuyển sang social
+pub fn apply(result: &mut ExampleResult) {
+    if let Some(web) = result.web.take() {
+        match clean_url(&web) {
+            Some(url) if is_social(&url) => {
+                if result.social.is_none() {
+                    result.social = Some(url);
+                } else {
+                    append_note(result, &format!("Link khác: {}", url));
+                }
+            }
+            Some(url) => result.web = Some(url),
+            // Không parse được: giữ nguyên để editor kiểm tra
+            None => result.web = Some(web),
+        }
+    }
+
+    result.social = result.social.take().map(|url| clean_url(&url).unwrap_or(url));
+    result.mail = result.mail.take().map(|mail| clean_email(&mail).unwrap_or(mail));
+}
+
+// "HTTP://WWW.Example.vn/?utm_source=google#top" -> "https://www.example.vn"
+pub fn clean_url(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+
+    let with_scheme = if raw.contains("://") {
+        raw.to_string()
+    } else {
+        format!("https://{}", raw)
+    };
+
+    // Url::parse đã chuyển host về chữ thường
+    let mut url = Url::parse(&with_scheme).ok()?;
+    match url.scheme() {
+        "https" => {}
+        "http" => url.set_scheme("https").ok()?,
+        _ => return None,
+    }
+    url.host_str()?;
+    url.set_fragment(None);
+
+    let params: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !is_tracking_param(key))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    if params.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(params);
+    }
+
+    let mut cleaned = url.to_string();
+    if url.path() == "/" && url.query().is_none() {
+        cleaned.pop();
+    }
+
+    Some(cleaned)
+}
+
+fn is_tracking_param(key: &str) -> bool {
+    let key = key.to_lowercase();
+    key.starts_with("utm_") || TRACKING_PARAMS.contains(&key.as_str())
+}
+
+pub fn is_social(url: &str) -> bool {
+    let Some(host) = Url::parse(url).ok().and_then(|u| u.host_str().map(|h| h.to_string())) else {
+        return false;
+    };
+
+    SOCIAL_DOMAINS
+        .iter()
+        .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+}
+
+// "mailto:Info@Example.VN" -> "info@example.vn", None nếu không phải email hợp lệ
+pub fn clean_email(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let raw = match raw.get(..7) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("mailto:") => &raw[7..],
+        _ => raw,
+    };
+    let email = raw.split('?').next()?.trim().trim_end_matches('.').to_lowercase();
+
+    let (local, domain) = email.split_once('@')?;
+    if local.is_empty() || domain.contains('@') {
+        return None;
+    }
+
+    let valid_chars = |s: &str, extra: &[char]| s.chars().all(|c| c.is_ascii_alphanumeric() || extra.contains(&c));
+    if !valid_chars(local, &['.', '_', '%', '+', '-']) || !valid_chars(domain, &['.', '-']) {
+        return None;
+    }
+
+    let tld = domain.rsplit_once('.')?.1;
+    if tld.len() < 2 || !tld.chars().all(|c| c.is_ascii_alphabetic()) || IMAGE_EXTENSIONS.contains(&tld) {
+        return None;
+    }
+
+    Some(email)
+}
+
+// Domain của website để làm cache key: "https://www.example.vn/lien-he" -> "example.vn"
+pub fn domain(website: &str) -> Option<String> {
+    let url = Url::parse(website).ok()?;
+    let host = url.host_str()?;
+    Some(host.strip_prefix("www.").unwrap_or(host).to_string())
+}
+
+// Tìm email trên website (trang chủ và các trang liên hệ phổ biến). Tổng thời gian bị giới hạn
+// bởi timeout_ms, mỗi trang chỉ đọc tối đa max_bytes và được retry theo policy.
+// Ok(None): website không có email; Err: timeout hoặc có trang lỗi tạm thời (không nên cache)
+pub async fn fetch_email(
+    client: &reqwest::Client,
+    website: &str,
+    timeout_ms: u64,
+    max_bytes: usize,
+    retry: RetryPolicy,
+) -> Result<Option<String>, String> {
+    let url = Url::parse(website).map_err(|e| e.to_string())?;
+    let origin = url.origin().ascii_serialization();
+
+    let search = async {
+        let mut last_error = None;
+        for path in CONTACT_PATHS {
+            let page_url = if path.is_empty() { website.to_string() } else { format!("{}{}", origin, path) };
+            match fetch_page(client, &page_url, max_bytes, retry).await {
+                Ok(Some(html)) => {
+                    if let Some(email) = extract_email(&html) {
+                        println!("Found email {} on {}", email, page_url);
+                        return Ok(Some(email));
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => last_error = Some(format!("{}: {}", page_url, e)),
+            }
+        }
+
+        match last_error {
+            Some(e) => Err(e),
+            None => Ok(None),
+        }
+    };
+
+    match tokio::time::timeout(Duration::from_millis(timeout_ms), search).await {
+        Ok(email) => email,
+        Err(_) => Err(format!("timed out after {}ms", timeout_ms)),
+    }
+}
+
+// GET một trang HTML, dừng đọc khi vượt quá max_bytes. Retry như upstream::get_json khi gặp
+// 5xx, 429 hoặc lỗi kết nối; Ok(None) khi trang không tồn tại hoặc không phải HTML
+async fn fetch_page(client: &reqwest::Client, url: &str, max_bytes: usize, retry: RetryPolicy) -> Result<Option<String>, String> {
+    let attempts = retry.max_retries + 1;
+    let mut last_error = String::new();
+
+    for attempt in 1..=attempts {
+        if attempt > 1 {
+            tokio::time::sleep(Duration::from_millis(retry.delay_ms(attempt))).await;
+        }
+
+        let mut response = match client.get(url).send().await {
+            Ok(response) => response,
+            Err(e) if e.is_timeout() || e.is_connect() || e.is_request() => {
+                last_error = e.without_url().to_string();
+                continue;
+            }
+            Err(_) => return Ok(None),
+        };
+
+        let status = response.status();
+        if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            last_error = format!("HTTP {}", status);
+            continue;
+        }
+        if !status.is_success() {
+            return Ok(None);
+        }
+
+        let is_html = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map_or(true, |v| v.contains("html"));
+        if !is_html {
+            return Ok(None);
+        }
+
+        let mut body = Vec::new();
+        let complete = loop {
+            match response.chunk().await {
+                Ok(Some(chunk)) => {
+                    body.extend_from_slice(&chunk);
+                    if body.len() >= max_bytes {
+                        body.truncate(max_bytes);
+                        break true;
+                    }
+                }
+                Ok(None) => break true,
+                Err(e) => {
+                    last_error = e.without_url().to_string();
+                    break false;
+                }
+            }
+        };
+        if !complete {
+            continue;
+        }
+
+        return Ok(Some(String::from_utf8_lossy(&body).into_owned()));
+    }
+
+    Err(format!("failed after {} attempts ({})", attempts, last_error))
+}
+
+// Lấy email đầu tiên trong HTML: ưu tiên link mailto:, sau đó tới email dạng text
+fn extract_email(html: &str) -> Option<String> {
+    let lower = html.to_lowercase();
+
+    let from_mailto = lower.match_indices("mailto:").find_map(|(i, _)| {
+        let rest = &lower[i..];
+        let end = rest.find(['"', '\'', '>', ' ', '<']).unwrap_or(rest.len());
+        clean_email(&rest[..end])
+    });
+    if from_mailto.is_some() {
+        return from_mailto;
+    }
+
+    let is_local_char = |c: char| c.is_ascii_alphanumeric() || "._%+-".contains(c);
+    let is_domain_char = |c: char| c.is_ascii_alphanumeric() || ".-".contains(c);
+
+    lower.match_indices('@').find_map(|(i, _)| {
+        let start = lower[..i]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| !is_local_char(*c))
+            .map_or(0, |(p, c)| p + c.len_utf8());
+        let end = lower[i + 1..].find(|c: char| !is_domain_char(c)).map_or(lower.len(), |p| i + 1 + p);
+        clean_email(&lower[start..end])
+    })
+}
@@ -0,0 +1,483 @@
+use geojson::{GeoJson, Value as GeometryValue};
+use rstar::primitives::GeomWithData;
+use rstar::{RTree, AABB};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::brand::fold;
+use crate::haversine_distance;
+use crate::type_mapping::split_csv_line;
+
+// Số mét trên một độ vĩ
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
+// Tên cột/thuộc tính khi import từ file export của layer
+const ID_KEYS: &[&str] = &["be_id", "id", "objectid", "fid"];
+const GOOGLE_ID_KEYS: &[&str] = &["google_id", "place_id"];
+const NAME_KEYS: &[&str] = &["poi_vn", "name", "poi_en"];
+const LAT_KEYS: &[&str] = &["latitude", "lat", "y"];
+const LNG_KEYS: &[&str] = &["longitude", "lng", "lon", "x"];
+
+// Một điểm đã xử lý hoặc đã có trên layer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DupRecord {
+    pub id: String,
+    #[serde(default)]
+    pub google_id: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub lat: f64,
+    pub lng: f64,
+}
+
+// Bản ghi trùng tìm được và khoảng cách (mét) tới điểm đang xử lý
+#[derive(Debug, Clone)]
+pub struct DupMatch {
+    pub id: String,
+    pub distance: f64,
+}
+
+// Điểm [lng, lat] trong R-tree kèm chỉ số bản ghi
+type IndexedPoint = GeomWithData<[f64; 2], usize>;
+
+#[derive(Default)]
+struct DupState {
+    records: Vec<DupRecord>,
+    tree: RTree<IndexedPoint>,
+    by_google_id: HashMap<String, Vec<usize>>,
+    by_id: HashMap<String, usize>,
+}
+
+impl DupState {
+    fn new(records: Vec<DupRecord>) -> Self {
+        let points = records
+            .iter()
+            .enumerate()
+            .map(|(index, r)| IndexedPoint::new([r.lng, r.lat], index))
+            .collect();
+
+        let mut by_google_id: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, record) in records.iter().enumerate() {
+            if let Some(google_id) = &record.google_id {
+                by_google_id.entry(google_id.clone()).or_default().push(index);
+            }
+        }
+
+        let by_id = records.iter().enumerate().map(|(index, r)| (r.id.clone(), index)).collect();
+
+        Self {
+            records,
+            tree: RTree::bulk_load(points),
+            by_google_id,
+            by_id,
+        }
+    }
+
+    fn push(&mut self, record: DupRecord) {
+        let index = self.records.len();
+        self.tree.insert(IndexedPoint::new([record.lng, record.lat], index));
+        if let Some(google_id) = &record.google_id {
+            self.by_google_id.entry(google_id.clone()).or_default().push(index);
+        }
+        self.by_id.insert(record.id.clone(), index);
+        self.records.push(record);
+    }
+
+    // Thay bản ghi đã có cùng id (xử lý lại), cập nhật vị trí trong R-tree và google_id
+    fn replace(&mut self, index: usize, record: DupRecord) {
+        let old = &self.records[index];
+        if old.lat != record.lat || old.lng != record.lng {
+            self.tree.remove(&IndexedPoint::new([old.lng, old.lat], index));
+            self.tree.insert(IndexedPoint::new([record.lng, record.lat], index));
+        }
+
+        if old.google_id != record.google_id {
+            if let Some(indexes) = old.google_id.as_ref().and_then(|id| self.by_google_id.get_mut(id)) {
+                indexes.retain(|i| *i != index);
+            }
+            if let Some(google_id) = &record.google_id {
+                self.by_google_id.entry(google_id.clone()).or_default().push(index);
+            }
+        }
+
+        self.records[index] = record;
+    }
+}
+
+// Bản ghi trong chỉ mục chính là điểm đang xử lý (gửi lại cùng điểm): cùng id,
+// hoặc cùng google_id tại đúng tọa độ đó
+fn is_same_record(record: &DupRecord, query: &DupRecord) -> bool {
+    record.id == query.id
+        || (record.google_id.is_some() && record.google_id == query.google_id && record.lat == query.lat && record.lng == query.lng)
+}
+
+// Chỉ mục các điểm đã trả về (và import từ layer) để phát hiện POI bị số hóa trùng,
+// lưu xuống file dup_index.json
+pub struct DupIndex {
+    path: Option<PathBuf>,
+    state: Mutex<DupState>,
+    // Có thay đổi chưa ghi xuống file (ghi định kỳ bằng flush, không ghi trong request)
+    dirty: AtomicBool,
+}
+
+impl DupIndex {
+    // Load từ file, nếu lỗi thì bắt đầu với chỉ mục rỗng
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let records: Vec<DupRecord> = path
+            .as_ref()
+            .filter(|p| p.exists())
+            .and_then(|p| match fs::read_to_string(p) {
+                Ok(content) => match serde_json::from_str(&content) {
+                    Ok(records) => Some(records),
+                    Err(e) => {
+                        println!("Error parsing dup index file: {}, starting empty", e);
+                        None
+                    }
+                },
+                Err(e) => {
+                    println!("Error reading dup index file: {}, starting empty", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        println!("Loaded {} records into dup index", records.len());
+
+        Self {
+            path,
+            state: Mutex::new(DupState::new(records)),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    // Tìm bản ghi trước đó trong bán kính radius_m trùng google_id, hoặc có tên gần giống
+    // (độ giống >= similarity). Bỏ qua chính điểm đang xử lý (xem is_same_record), điểm khác
+    // dù cách chưa tới 1m vẫn tính là trùng
+    pub fn find(&self, query: &DupRecord, radius_m: f64, similarity: f64) -> Option<DupMatch> {
+        let state = self.state.lock().ok()?;
+        let distance_to = |record: &DupRecord| haversine_distance(query.lat, query.lng, record.lat, record.lng);
+
+        let by_google_id = query
+            .google_id
+            .as_ref()
+            .and_then(|id| state.by_google_id.get(id))
+            .into_iter()
+            .flatten()
+            .map(|index| &state.records[*index])
+            .filter(|record| !is_same_record(record, query))
+            .map(|record| (record, distance_to(record)))
+            .filter(|(_, distance)| *distance <= radius_m)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((record, distance)) = by_google_id {
+            return Some(DupMatch {
+                id: record.id.clone(),
+                distance,
+            });
+        }
+
+        let name = fold_name(query.name.as_deref()?);
+        if name.is_empty() {
+            return None;
+        }
+
+        let (lat, lng) = (query.lat, query.lng);
+        let delta_lat = radius_m / METERS_PER_DEGREE;
+        let delta_lng = radius_m / (METERS_PER_DEGREE * lat.to_radians().cos().max(0.01));
+        let envelope = AABB::from_corners([lng - delta_lng, lat - delta_lat], [lng + delta_lng, lat + delta_lat]);
+
+        state
+            .tree
+            .locate_in_envelope(&envelope)
+            .map(|point| &state.records[point.data])
+            .filter(|record| !is_same_record(record, query))
+            .map(|record| (record, distance_to(record)))
+            .filter(|(_, distance)| *distance <= radius_m)
+            .filter(|(record, _)| {
+                record
+                    .name
+                    .as_deref()
+                    .is_some_and(|other| name_similarity(&name, &fold_name(other)) >= similarity)
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(record, distance)| DupMatch {
+                id: record.id.clone(),
+                distance,
+            })
+    }
+
+    // Thêm bản ghi; nếu đã có bản ghi cùng id (xử lý lại) thì thay bản ghi đó
+    pub fn insert(&self, record: DupRecord) {
+        if let Ok(mut state) = self.state.lock() {
+            match state.by_id.get(&record.id).copied() {
+                Some(index) => state.replace(index, record),
+                None => state.push(record),
+            }
+
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+    }
+
+    // Import điểm từ file export của layer (GeoJSON Point hoặc CSV có cột lat/lng), trả về số điểm đã thêm.
+    // Bỏ qua bản ghi có id đã có trong chỉ mục (import lại cùng một file không nhân đôi điểm)
+    pub fn import(&self, path: &Path) -> Result<usize, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("Error reading {:?}: {}", path, e))?;
+        let content = content.trim_start_matches('\u{feff}');
+
+        let is_csv = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+        let records = if is_csv { parse_csv(content)? } else { parse_geojson(content)? };
+        let total = records.len();
+        let mut count = 0;
+
+        {
+            let mut state = self.state.lock().map_err(|_| "Dup index is unavailable".to_string())?;
+            for record in records {
+                if !state.by_id.contains_key(&record.id) {
+                    state.push(record);
+                    count += 1;
+                }
+            }
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+        self.flush();
+
+        println!("Imported {} of {} records into dup index from {:?}", count, total, path);
+        Ok(count)
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = DupState::default();
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        self.flush();
+        println!("Dup index cleared");
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().map(|s| s.records.len()).unwrap_or(0)
+    }
+
+    // Ghi dup_index.json nếu có thay đổi. Chỉ giữ lock lúc serialize, ghi file sau khi nhả lock
+    pub fn flush(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return;
+        }
+
+        let json = match self.state.lock() {
+            Ok(state) => serde_json::to_string(&state.records),
+            Err(_) => return,
+        };
+
+        if let Some(dir) = path.parent() {
+            if !dir.exists() {
+                if let Err(e) = fs::create_dir_all(dir) {
+                    println!("Error creating dup index directory: {}", e);
+                    return;
+                }
+            }
+        }
+
+        match json {
+            Ok(json) => {
+                if let Err(e) = fs::write(path, json) {
+                    println!("Error writing dup index file: {}", e);
+                    self.dirty.store(true, Ordering::Relaxed);
+                }
+            }
+            Err(e) => println!("Error serializing dup index: {}", e),
+        }
+    }
+}
+
+fn parse_geojson(content: &str) -> Result<Vec<DupRecord>, String> {
+    let features = match content.parse::<GeoJson>().map_err(|e| e.to_string())? {
+        GeoJson::FeatureCollection(collection) => collection.features,
+        GeoJson::Feature(feature) => vec![feature],
+        GeoJson::Geometry(_) => return Err("GeoJSON has no features".to_string()),
+    };
+
+    let records = features
+        .iter()
+        .filter_map(|feature| {
+            let GeometryValue::Point(position) = &feature.geometry.as_ref()?.value else {
+                return None;
+            };
+            let (lng, lat) = (*position.first()?, *position.get(1)?);
+
+            let property = |keys: &[&str]| {
+                keys.iter().find_map(|key| match feature.property(*key)? {
+                    Value::String(s) if !s.is_empty() => Some(s.clone()),
+                    Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                })
+            };
+            let feature_id = feature.id.as_ref().map(|id| match id {
+                geojson::feature::Id::String(s) => s.clone(),
+                geojson::feature::Id::Number(n) => n.to_string(),
+            });
+
+            Some(DupRecord {
+                id: property(ID_KEYS).or(feature_id).unwrap_or_else(|| point_id(lat, lng)),
+                google_id: property(GOOGLE_ID_KEYS),
+                name: property(NAME_KEYS),
+                lat,
+                lng,
+            })
+        })
+        .collect();
+
+    Ok(records)
+}
+
+fn parse_csv(content: &str) -> Result<Vec<DupRecord>, String> {
+    let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<String> = split_csv_line(lines.next().ok_or("CSV file is empty")?)
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+
+    let column = |keys: &[&str]| keys.iter().find_map(|key| header.iter().position(|h| h == key));
+    let lat_column = column(LAT_KEYS).ok_or("CSV has no latitude column")?;
+    let lng_column = column(LNG_KEYS).ok_or("CSV has no longitude column")?;
+    let (id_column, google_id_column, name_column) = (column(ID_KEYS), column(GOOGLE_ID_KEYS), column(NAME_KEYS));
+
+    let records = lines
+        .filter_map(|line| {
+            let fields = split_csv_line(line);
+            let field = |column: Option<usize>| {
+                column
+                    .and_then(|c| fields.get(c))
+                    .map(|f| f.trim().to_string())
+                    .filter(|f| !f.is_empty())
+            };
+
+            let lat: f64 = field(Some(lat_column))?.parse().ok()?;
+            let lng: f64 = field(Some(lng_column))?.parse().ok()?;
+
+            Some(DupRecord {
+                id: field(id_column).unwrap_or_else(|| point_id(lat, lng)),
+                google_id: field(google_id_column),
+                name: field(name_column),
+                lat,
+                lng,
+            })
+        })
+        .collect();
+
+    Ok(records)
+}
+
+// Id cho điểm không có id/google_id: theo tọa độ, giống id của điểm đã xử lý
+pub fn point_id(lat: f64, lng: f64) -> String {
+    format!("{:.6},{:.6}", lat, lng)
+}
+
+// Key so sánh tên: bỏ dấu, chữ thường, chỉ giữ chữ/số và một khoảng trắng giữa các từ
+fn fold_name(name: &str) -> String {
+    fold(name)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Độ giống của hai tên (0..1): 1 nếu tên này chứa tên kia, ngược lại theo khoảng cách Levenshtein
+fn name_similarity(a: &str, b: &str) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let (shorter, longer) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if shorter.chars().count() >= 4 && longer.contains(shorter) {
+        return 1.0;
+    }
+
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            current[j + 1] = (previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    1.0 - previous[b.len()] as f64 / a.len().max(b.len()) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, google_id: Option<&str>, name: &str, lat: f64, lng: f64) -> DupRecord {
+        DupRecord {
+            id: id.to_string(),
+            google_id: google_id.map(|g| g.to_string()),
+            name: Some(name.to_string()),
+            lat,
+            lng,
+        }
+    }
+
+    #[test]
+    fn resend_of_same_point_is_not_a_duplicate() {
+        let index = DupIndex::load(None);
+        let first = record(&point_id(10.7769, 106.7009), Some("abc"), "Circle K", 10.7769, 106.7009);
+        index.insert(first.clone());
+
+        assert!(index.find(&first, 50.0, 0.8).is_none());
+
+        // Gửi lại thì thay bản ghi cũ, không thêm mới
+        index.insert(first);
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn sub_metre_neighbour_is_a_duplicate() {
+        let index = DupIndex::load(None);
+        index.insert(record("layer-1", None, "Circle K", 10.7769, 106.7009));
+
+        // Cách ~0.5m, id khác
+        let query = record(&point_id(10.776904, 106.7009), None, "Circle K", 10.776904, 106.7009);
+        let found = index.find(&query, 50.0, 0.8).expect("neighbour flagged");
+        assert_eq!(found.id, "layer-1");
+        assert!(found.distance < 1.0);
+    }
+
+    #[test]
+    fn google_id_match_is_limited_to_radius() {
+        let index = DupIndex::load(None);
+        index.insert(record("layer-1", Some("abc"), "Circle K", 10.7769, 106.7009));
+
+        let near = record(&point_id(10.7770, 106.7009), Some("abc"), "Khác", 10.7770, 106.7009);
+        assert_eq!(index.find(&near, 50.0, 0.8).map(|m| m.id).as_deref(), Some("layer-1"));
+
+        // Cách ~1.1km
+        let far = record(&point_id(10.7869, 106.7009), Some("abc"), "Khác", 10.7869, 106.7009);
+        assert!(index.find(&far, 50.0, 0.8).is_none());
+    }
+
+    #[test]
+    fn same_google_id_at_same_coordinates_is_a_resend() {
+        let index = DupIndex::load(None);
+        index.insert(record("layer-1", Some("abc"), "Circle K", 10.7769, 106.7009));
+
+        let query = record(&point_id(10.7769, 106.7009), Some("abc"), "Circle K", 10.7769, 106.7009);
+        assert!(index.find(&query, 50.0, 0.8).is_none());
+    }
+}
@@ -0,0 +1,217 @@
+use serde::de::value::Error as ValueError;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+
+use crate::history::HistoryEntry;
+use crate::ExampleResult;
+
+// Field không export (danh sách candidate lồng nhau, id chờ chọn candidate của widget)
+const SKIPPED_FIELDS: &[&str] = &["candidates", "selection_id"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    GeoJson,
+    // UTF-8 có BOM để Excel hiển thị đúng tiếng Việt
+    Csv,
+    Kml,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::GeoJson => "geojson",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Kml => "kml",
+        }
+    }
+}
+
+// Một kết quả để export: tọa độ điểm (điểm editor click) và giá trị các field theo tên serde
+struct ExportRow {
+    id: i64,
+    lat: f64,
+    lng: f64,
+    fields: Map<String, Value>,
+}
+
+// Ghi các dòng lịch sử ra file theo format, trả về số điểm đã ghi
+pub fn export(entries: Vec<HistoryEntry>, format: ExportFormat, path: &Path) -> Result<usize, String> {
+    let rows: Vec<ExportRow> = entries
+        .into_iter()
+        .filter_map(|entry| {
+            let mut result = entry.result?;
+            // Kết quả không có tọa độ địa điểm: dùng điểm click
+            result.latitude.get_or_insert(entry.summary.lat);
+            result.longitude.get_or_insert(entry.summary.lng);
+
+            let Value::Object(fields) = serde_json::to_value(&result).ok()? else {
+                return None;
+            };
+
+            Some(ExportRow {
+                id: entry.summary.id,
+                lat: entry.summary.lat,
+                lng: entry.summary.lng,
+                fields,
+            })
+        })
+        .collect();
+
+    let columns = result_columns();
+    let content = match format {
+        ExportFormat::GeoJson => to_geojson(&rows, &columns),
+        ExportFormat::Csv => to_csv(&rows, &columns),
+        ExportFormat::Kml => to_kml(&rows, &columns),
+    };
+
+    if let Some(dir) = path.parent() {
+        if !dir.as_os_str().is_empty() && !dir.exists() {
+            fs::create_dir_all(dir).map_err(|e| format!("Error creating export directory: {}", e))?;
+        }
+    }
+    fs::write(path, content).map_err(|e| format!("Error writing {:?}: {}", path, e))?;
+
+    println!("Exported {} results to {:?}", rows.len(), path);
+    Ok(rows.len())
+}
+
+// Tên serde các field của ExampleResult theo thứ tự khai báo (cố định, kể cả field None ở mọi dòng).
+// Lấy từ danh sách field mà derive(Deserialize) truyền cho deserialize_struct
+fn result_columns() -> Vec<&'static str> {
+    struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for FieldNames<'_> {
+        type Error = ValueError;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, ValueError> {
+            Err(de::Error::custom("only structs are supported"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, ValueError> {
+            *self.0 = fields;
+            Err(de::Error::custom("field names captured"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = ExampleResult::deserialize(FieldNames(&mut fields));
+
+    fields.iter().copied().filter(|field| !SKIPPED_FIELDS.contains(field)).collect()
+}
+
+// Giá trị một cột của dòng (field None không có trong JSON -> null)
+fn column_value<'a>(row: &'a ExportRow, column: &str) -> &'a Value {
+    row.fields.get(column).unwrap_or(&Value::Null)
+}
+
+// Giá trị dạng text cho CSV/KML (null -> rỗng, object/array -> JSON)
+fn text_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn to_geojson(rows: &[ExportRow], columns: &[&str]) -> String {
+    // Ghi properties thủ công để giữ thứ tự cột (serde_json::Map sắp xếp key theo tên)
+    let features: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let properties: Vec<String> = columns
+                .iter()
+                .map(|column| format!("{}:{}", Value::from(*column), column_value(row, column)))
+                .collect();
+
+            format!(
+                "{{\"type\":\"Feature\",\"id\":{},\"geometry\":{{\"type\":\"Point\",\"coordinates\":[{},{}]}},\"properties\":{{{}}}}}",
+                row.id,
+                row.lng,
+                row.lat,
+                properties.join(",")
+            )
+        })
+        .collect();
+
+    format!("{{\"type\":\"FeatureCollection\",\"features\":[\n{}\n]}}\n", features.join(",\n"))
+}
+
+fn to_csv(rows: &[ExportRow], columns: &[&str]) -> String {
+    let mut csv = String::from("\u{feff}");
+
+    csv.push_str(&columns.iter().map(|c| csv_field(c)).collect::<Vec<_>>().join(","));
+    csv.push_str("\r\n");
+
+    for row in rows {
+        let line: Vec<String> = columns
+            .iter()
+            .map(|column| csv_field(&text_value(column_value(row, column))))
+            .collect();
+        csv.push_str(&line.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_kml(rows: &[ExportRow], columns: &[&str]) -> String {
+    let mut kml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n",
+    );
+
+    for row in rows {
+        let field = |name: &str| row.fields.get(name).map(text_value);
+        let name = field("poi_vn").filter(|n| !n.is_empty()).or_else(|| field("address")).unwrap_or_default();
+
+        kml.push_str(&format!("<Placemark id=\"{}\">\n<name>{}</name>\n", row.id, xml_escape(&name)));
+        if let Some(address) = field("address").filter(|a| !a.is_empty()) {
+            kml.push_str(&format!("<description>{}</description>\n", xml_escape(&address)));
+        }
+
+        kml.push_str("<ExtendedData>\n");
+        for column in columns {
+            kml.push_str(&format!(
+                "<Data name=\"{}\"><value>{}</value></Data>\n",
+                xml_escape(column),
+                xml_escape(&text_value(column_value(row, column)))
+            ));
+        }
+        kml.push_str("</ExtendedData>\n");
+
+        kml.push_str(&format!("<Point><coordinates>{},{}</coordinates></Point>\n</Placemark>\n", row.lng, row.lat));
+    }
+
+    kml.push_str("</Document>\n</kml>\n");
+    kml
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
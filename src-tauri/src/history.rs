@@ -0,0 +1,331 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::ExampleResult;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at TEXT NOT NULL,
+    lat REAL NOT NULL,
+    lng REAL NOT NULL,
+    status TEXT NOT NULL,
+    poi_vn TEXT,
+    address TEXT NOT NULL,
+    google_id TEXT,
+    duration_ms INTEGER NOT NULL,
+    error TEXT,
+    raw_json TEXT,
+    result_json TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS history_created_at ON history (created_at);
+";
+
+const SUMMARY_COLUMNS: &str = "id, created_at, lat, lng, status, poi_vn, address, google_id, duration_ms, error";
+
+// Một lần xử lý /process cần lưu lại
+pub struct HistoryRecord {
+    pub lat: f64,
+    pub lng: f64,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+    // Response gốc của upstream theo cache key
+    pub raw: Value,
+    pub result: ExampleResult,
+}
+
+// Dòng lịch sử rút gọn cho danh sách
+#[derive(Debug, Clone, Serialize)]
+pub struct HistorySummary {
+    pub id: i64,
+    pub created_at: String,
+    pub lat: f64,
+    pub lng: f64,
+    pub status: String,
+    pub poi_vn: Option<String>,
+    pub address: String,
+    pub google_id: Option<String>,
+    pub duration_ms: i64,
+    pub error: Option<String>,
+}
+
+// Đầy đủ một dòng lịch sử: kèm response gốc và kết quả cuối cùng
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEntry {
+    #[serde(flatten)]
+    pub summary: HistorySummary,
+    pub raw: Option<Value>,
+    pub result: Option<ExampleResult>,
+}
+
+// Lịch sử mọi điểm đã xử lý, lưu trong SQLite (history.db) để khôi phục sau khi app bị tắt
+pub struct HistoryDb {
+    conn: Mutex<Connection>,
+}
+
+impl HistoryDb {
+    // Mở (hoặc tạo) history.db, lỗi thì dùng database trong bộ nhớ
+    pub fn open(path: Option<PathBuf>) -> Self {
+        let conn = path
+            .and_then(|path| {
+                if let Some(dir) = path.parent() {
+                    if !dir.exists() {
+                        if let Err(e) = fs::create_dir_all(dir) {
+                            println!("Error creating history directory: {}", e);
+                        }
+                    }
+                }
+
+                match Connection::open(&path).and_then(|conn| conn.execute_batch(SCHEMA).map(|_| conn)) {
+                    Ok(conn) => {
+                        println!("History database opened: {:?}", path);
+                        Some(conn)
+                    }
+                    Err(e) => {
+                        println!("Error opening history database {:?}: {}, using in-memory history", path, e);
+                        None
+                    }
+                }
+            })
+            .unwrap_or_else(|| {
+                let conn = Connection::open_in_memory().expect("failed to open in-memory history database");
+                if let Err(e) = conn.execute_batch(SCHEMA) {
+                    println!("Error creating in-memory history schema: {}", e);
+                }
+                conn
+            });
+
+        Self { conn: Mutex::new(conn) }
+    }
+
+    // Ghi một lần xử lý, trả về id của dòng mới
+    pub fn record(&self, record: &HistoryRecord) -> Result<i64, String> {
+        let result_json = serde_json::to_string(&record.result).map_err(|e| e.to_string())?;
+        let raw_json = (!record.raw.is_null()).then(|| record.raw.to_string());
+        // Giờ GMT+7 để lọc theo ngày làm việc của editor ("2024-05-01T...+07:00")
+        let gmt_plus_7 = chrono::FixedOffset::east_opt(7 * 3600).unwrap();
+        let created_at = chrono::Utc::now().with_timezone(&gmt_plus_7).to_rfc3339();
+
+        let conn = self.conn.lock().map_err(|_| "History database is unavailable".to_string())?;
+        conn.execute(
+            "INSERT INTO history (created_at, lat, lng, status, poi_vn, address, google_id, duration_ms, error, raw_json, result_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                created_at,
+                record.lat,
+                record.lng,
+                record.result.status,
+                record.result.poi_vn,
+                record.result.address,
+                record.result.google_id,
+                record.duration_ms as i64,
+                record.error,
+                raw_json,
+                result_json,
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok(conn.last_insert_rowid())
+    }
+
+    // Cập nhật kết quả cuối cùng của dòng đã ghi (editor chọn candidate sau khi xử lý xong),
+    // response gốc mới được gộp thêm vào raw_json
+    pub fn update(&self, id: i64, result: &ExampleResult, raw: &Value) -> Result<(), String> {
+        let result_json = serde_json::to_string(result).map_err(|e| e.to_string())?;
+        let raw_json = raw.as_object().filter(|raw| !raw.is_empty()).map(|_| raw.to_string());
+
+        let conn = self.conn.lock().map_err(|_| "History database is unavailable".to_string())?;
+        conn.execute(
+            "UPDATE history SET status = ?2, poi_vn = ?3, address = ?4, google_id = ?5, result_json = ?6,
+                 raw_json = CASE WHEN ?7 IS NULL THEN raw_json ELSE json_patch(COALESCE(raw_json, '{}'), ?7) END
+             WHERE id = ?1",
+            params![id, result.status, result.poi_vn, result.address, result.google_id, result_json, raw_json],
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    // Các dòng mới nhất trước
+    pub fn list(&self, limit: usize, offset: usize) -> Result<Vec<HistorySummary>, String> {
+        let conn = self.conn.lock().map_err(|_| "History database is unavailable".to_string())?;
+        let mut stmt = conn
+            .prepare(&format!("SELECT {} FROM history ORDER BY id DESC LIMIT ?1 OFFSET ?2", SUMMARY_COLUMNS))
+            .map_err(|e| e.to_string())?;
+
+        let rows = stmt
+            .query_map(params![limit as i64, offset as i64], summary_from_row)
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+    }
+
+    // Tìm theo tên, địa chỉ, google_id hoặc lỗi (không phân biệt hoa thường với chữ ASCII)
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<HistorySummary>, String> {
+        let pattern = format!("%{}%", query.trim().replace('%', "\\%").replace('_', "\\_"));
+
+        let conn = self.conn.lock().map_err(|_| "History database is unavailable".to_string())?;
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM history
+                 WHERE poi_vn LIKE ?1 ESCAPE '\\' OR address LIKE ?1 ESCAPE '\\'
+                    OR google_id LIKE ?1 ESCAPE '\\' OR error LIKE ?1 ESCAPE '\\'
+                 ORDER BY id DESC LIMIT ?2",
+                SUMMARY_COLUMNS
+            ))
+            .map_err(|e| e.to_string())?;
+
+        let rows = stmt
+            .query_map(params![pattern, limit as i64], summary_from_row)
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+    }
+
+    // Các dòng để export theo danh sách id, hoặc theo khoảng ngày (YYYY-MM-DD, GMT+7, tính cả hai đầu),
+    // cũ nhất trước
+    pub fn export_entries(&self, ids: Option<&[i64]>, from: Option<&str>, to: Option<&str>) -> Result<Vec<HistoryEntry>, String> {
+        let mut conditions = Vec::new();
+        let mut values: Vec<rusqlite::types::Value> = Vec::new();
+
+        if let Some(from) = from {
+            values.push(from.to_string().into());
+            conditions.push(format!("substr(created_at, 1, 10) >= ?{}", values.len()));
+        }
+        if let Some(to) = to {
+            values.push(to.to_string().into());
+            conditions.push(format!("substr(created_at, 1, 10) <= ?{}", values.len()));
+        }
+        if let Some(ids) = ids {
+            if ids.is_empty() {
+                return Ok(Vec::new());
+            }
+            // Truyền cả danh sách dưới dạng một mảng JSON (không bị giới hạn số tham số của SQLite)
+            let ids_json = serde_json::to_string(ids).map_err(|e| e.to_string())?;
+            values.push(ids_json.into());
+            conditions.push(format!("id IN (SELECT value FROM json_each(?{}))", values.len()));
+        }
+
+        let filter = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let conn = self.conn.lock().map_err(|_| "History database is unavailable".to_string())?;
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {}, raw_json, result_json FROM history {} ORDER BY id",
+                SUMMARY_COLUMNS, filter
+            ))
+            .map_err(|e| e.to_string())?;
+
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(values), entry_from_row)
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+    }
+
+    pub fn get(&self, id: i64) -> Result<Option<HistoryEntry>, String> {
+        let conn = self.conn.lock().map_err(|_| "History database is unavailable".to_string())?;
+        conn.query_row(
+            &format!("SELECT {}, raw_json, result_json FROM history WHERE id = ?1", SUMMARY_COLUMNS),
+            params![id],
+            entry_from_row,
+        )
+        .optional()
+        .map_err(|e| e.to_string())
+    }
+}
+
+// Dòng gồm SUMMARY_COLUMNS, raw_json, result_json
+fn entry_from_row(row: &Row) -> rusqlite::Result<HistoryEntry> {
+    let raw: Option<String> = row.get(10)?;
+    let result: String = row.get(11)?;
+    Ok(HistoryEntry {
+        summary: summary_from_row(row)?,
+        raw: raw.and_then(|raw| serde_json::from_str(&raw).ok()),
+        result: serde_json::from_str(&result).ok(),
+    })
+}
+
+fn summary_from_row(row: &Row) -> rusqlite::Result<HistorySummary> {
+    Ok(HistorySummary {
+        id: row.get(0)?,
+        created_at: row.get(1)?,
+        lat: row.get(2)?,
+        lng: row.get(3)?,
+        status: row.get(4)?,
+        poi_vn: row.get(5)?,
+        address: row.get(6)?,
+        google_id: row.get(7)?,
+        duration_ms: row.get(8)?,
+        error: row.get(9)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(db: &HistoryDb, raw: Value, result: ExampleResult) -> i64 {
+        db.record(&HistoryRecord {
+            lat: 10.7769,
+            lng: 106.7009,
+            duration_ms: 120,
+            error: None,
+            raw,
+            result,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn update_replaces_result_and_merges_raw_responses() {
+        let db = HistoryDb::open(None);
+        let id = record(&db, json!({ "google:geocode:a": { "status": "OK" } }), ExampleResult::default());
+
+        let selected = ExampleResult {
+            status: "A".to_string(),
+            poi_vn: Some("Circle K".to_string()),
+            ..Default::default()
+        };
+        db.update(id, &selected, &json!({ "google:placedetails:b": { "status": "OK" } })).unwrap();
+
+        let entry = db.get(id).unwrap().unwrap();
+        assert_eq!(entry.summary.poi_vn.as_deref(), Some("Circle K"));
+        assert_eq!(entry.result.unwrap().poi_vn.as_deref(), Some("Circle K"));
+        let raw = entry.raw.unwrap();
+        assert!(raw.get("google:geocode:a").is_some());
+        assert!(raw.get("google:placedetails:b").is_some());
+    }
+
+    #[test]
+    fn update_without_responses_keeps_raw() {
+        let db = HistoryDb::open(None);
+        let id = record(&db, json!({ "google:geocode:a": { "status": "OK" } }), ExampleResult::default());
+
+        db.update(id, &ExampleResult::default(), &Value::Null).unwrap();
+
+        assert_eq!(db.get(id).unwrap().unwrap().raw, Some(json!({ "google:geocode:a": { "status": "OK" } })));
+    }
+
+    #[test]
+    fn export_entries_accepts_more_ids_than_sqlite_parameter_limit() {
+        let db = HistoryDb::open(None);
+        let first = record(&db, Value::Null, ExampleResult::default());
+        let second = record(&db, Value::Null, ExampleResult::default());
+        record(&db, Value::Null, ExampleResult::default());
+
+        // Vượt giới hạn 32766 tham số mặc định của SQLite
+        let mut ids: Vec<i64> = (1_000..40_000).collect();
+        ids.extend([second, first]);
+
+        let exported: Vec<i64> = db.export_entries(Some(&ids), None, None).unwrap().iter().map(|e| e.summary.id).collect();
+        assert_eq!(exported, vec![first, second]);
+    }
+}
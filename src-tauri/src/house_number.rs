@@ -0,0 +1,266 @@
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{append_note, ExampleResult};
+
+// Từ khóa hẻm/ngõ/kiệt (cấp 1) và ngách (cấp 2)
+const ALLEY_WORDS: &[&str] = &["hẻm", "ngõ", "kiệt", "hxh", "h.", "k.", "ng."];
+const SUB_ALLEY_WORDS: &[&str] = &["ngách", "ngh."];
+const NUMBER_WORDS: &[&str] = &["số", "sn"];
+
+// Số nhà đã tách: số nhà, hẻm/ngõ/kiệt, ngách (hẻm cấp 2 trở đi) và chuỗi gốc
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HouseNumber {
+    pub number: Option<String>,
+    pub alley: Option<String>,
+    pub sub_alley: Option<String>,
+    pub raw: String,
+}
+
+// Gán số nhà vào result: ưu tiên street_number, không có thì tách từ đầu formatted_address.
+// Chuỗi gốc luôn được giữ trong note
+pub fn apply(result: &mut ExampleResult, street_number: Option<&str>) {
+    let street_number = street_number.map(str::trim).filter(|s| !s.is_empty());
+    let parsed = match street_number {
+        Some(street_number) => parse(street_number),
+        None => extract_from_address(&result.address, result.st_name.as_deref()),
+    };
+
+    let Some(parsed) = parsed else {
+        // Không parse được street_number: giữ nguyên giá trị Google trả về
+        if let Some(street_number) = street_number {
+            result.house_num = Some(street_number.to_string());
+            append_note(result, street_number);
+        }
+        return;
+    };
+
+    append_note(result, &parsed.raw);
+
+    result.house_num = parsed.number;
+    result.alley = parsed.alley;
+    result.sub_alley = parsed.sub_alley;
+}
+
+// Parse chuỗi số nhà: "12/3A", "123-125", "Ngõ 45 ngách 6", "Số 8 ngõ 45", "Kiệt 12/7", "K12/7"
+pub fn parse(text: &str) -> Option<HouseNumber> {
+    let text: String = text.nfc().collect();
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+
+    let (parsed, consumed) = parse_tokens(&tokens);
+    if consumed == 0 {
+        return None;
+    }
+
+    Some(parsed)
+}
+
+// Tách số nhà ở đầu phần đầu tiên của formatted_address ("12/3A Lê Lợi, ...").
+// Bỏ qua khi phần đầu chính là tên đường (vd. đường "30 Tháng 4")
+pub fn extract_from_address(address: &str, st_name: Option<&str>) -> Option<HouseNumber> {
+    let first: String = address.split(',').next()?.trim().nfc().collect();
+    if first.is_empty() {
+        return None;
+    }
+
+    if let Some(st_name) = st_name {
+        let st_name: String = st_name.nfc().collect();
+        if st_name.ends_with(&first) {
+            return None;
+        }
+    }
+
+    let tokens: Vec<&str> = first.split_whitespace().collect();
+    let (parsed, consumed) = parse_tokens(&tokens);
+
+    // Phải còn lại tên đường phía sau số nhà
+    if consumed == 0 || consumed >= tokens.len() {
+        return None;
+    }
+
+    Some(parsed)
+}
+
+// Đọc các token số nhà từ đầu danh sách, trả về kết quả và số token đã dùng
+fn parse_tokens(tokens: &[&str]) -> (HouseNumber, usize) {
+    let mut parsed = HouseNumber::default();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let token = tokens[i].trim_end_matches(',');
+        let lower = token.to_lowercase();
+        let next = tokens.get(i + 1).map(|t| t.trim_end_matches(','));
+
+        if lower == "số" && next.map(|t| t.to_lowercase()) == Some("nhà".to_string()) {
+            // "số nhà 12": bỏ qua chữ "nhà"
+            let Some(value) = tokens.get(i + 2).filter(|t| is_number_token(t)) else {
+                break;
+            };
+            set_number_path(&mut parsed, value);
+            i += 3;
+        } else if NUMBER_WORDS.contains(&lower.as_str()) {
+            let Some(value) = next.filter(|t| is_number_token(t)) else {
+                break;
+            };
+            set_number_path(&mut parsed, value);
+            i += 2;
+        } else if ALLEY_WORDS.contains(&lower.as_str()) {
+            let Some(value) = next.filter(|t| is_number_token(t)) else {
+                break;
+            };
+            set_alley_path(&mut parsed, value);
+            i += 2;
+        } else if SUB_ALLEY_WORDS.contains(&lower.as_str()) {
+            let Some(value) = next.filter(|t| is_number_token(t)) else {
+                break;
+            };
+            parsed.sub_alley = Some(trim_zeros(value));
+            i += 2;
+        } else if let Some(value) = attached_alley(token) {
+            // "K12/7", "H45"
+            set_alley_path(&mut parsed, value);
+            i += 1;
+        } else if is_number_token(token) && parsed.number.is_none() {
+            set_number_path(&mut parsed, token);
+            i += 1;
+        } else {
+            break;
+        }
+    }
+
+    parsed.raw = tokens[..i].join(" ");
+    (parsed, i)
+}
+
+// "12/3A" -> hẻm 12, số 3A; "12/3/5" -> hẻm 12, ngách 3, số 5; "123-125" giữ nguyên dải số
+fn set_number_path(parsed: &mut HouseNumber, value: &str) {
+    let parts: Vec<String> = value.split('/').filter(|p| !p.is_empty()).map(trim_zeros).collect();
+
+    match parts.as_slice() {
+        [] => {}
+        [number] => parsed.number = Some(number.clone()),
+        [alley, number] => {
+            parsed.alley.get_or_insert_with(|| alley.clone());
+            parsed.number = Some(number.clone());
+        }
+        [alley, middle @ .., number] => {
+            parsed.alley.get_or_insert_with(|| alley.clone());
+            parsed.sub_alley.get_or_insert_with(|| middle.join("/"));
+            parsed.number = Some(number.clone());
+        }
+    }
+}
+
+// "Kiệt 12/7" -> kiệt 12, ngách 7
+fn set_alley_path(parsed: &mut HouseNumber, value: &str) {
+    let mut parts = value.split('/').filter(|p| !p.is_empty()).map(trim_zeros);
+
+    if let Some(alley) = parts.next() {
+        parsed.alley = Some(alley);
+    }
+
+    let rest: Vec<String> = parts.collect();
+    if !rest.is_empty() {
+        parsed.sub_alley = Some(rest.join("/"));
+    }
+}
+
+// Token dạng "K12/7", "H45" (chữ viết tắt dính liền số)
+fn attached_alley(token: &str) -> Option<&str> {
+    let mut chars = token.char_indices();
+    let (_, first) = chars.next()?;
+    let (offset, second) = chars.next()?;
+
+    if matches!(first, 'K' | 'k' | 'H' | 'h') && second.is_ascii_digit() {
+        Some(&token[offset..])
+    } else {
+        None
+    }
+}
+
+// Token số nhà: bắt đầu bằng số, chỉ gồm chữ/số, "/" và "-" (loại plus code "8Q7F+2X")
+fn is_number_token(token: &str) -> bool {
+    token.chars().next().is_some_and(|c| c.is_ascii_digit())
+        && token.chars().all(|c| c.is_alphanumeric() || c == '/' || c == '-')
+}
+
+// Bỏ số 0 ở đầu từng số trong dải ("012-014" -> "12-14", "00" -> "0")
+fn trim_zeros(value: &str) -> String {
+    value
+        .split('-')
+        .map(|part| {
+            let trimmed = part.trim_start_matches('0');
+            if trimmed.is_empty() && !part.is_empty() {
+                "0"
+            } else {
+                trimmed
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn house(number: Option<&str>, alley: Option<&str>, sub_alley: Option<&str>, raw: &str) -> HouseNumber {
+        HouseNumber {
+            number: number.map(String::from),
+            alley: alley.map(String::from),
+            sub_alley: sub_alley.map(String::from),
+            raw: raw.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_slash_path_into_alley_sub_alley_and_number() {
+        assert_eq!(parse("12/3/4"), Some(house(Some("4"), Some("12"), Some("3"), "12/3/4")));
+    }
+
+    #[test]
+    fn parses_number_word_prefix() {
+        assert_eq!(parse("Số 12A"), Some(house(Some("12A"), None, None, "Số 12A")));
+    }
+
+    #[test]
+    fn parses_kiet_with_sub_alley() {
+        assert_eq!(parse("Kiệt 5/7"), Some(house(None, Some("5"), Some("7"), "Kiệt 5/7")));
+    }
+
+    #[test]
+    fn parses_lowercase_hem() {
+        assert_eq!(parse("hẻm 12"), Some(house(None, Some("12"), None, "hẻm 12")));
+    }
+
+    #[test]
+    fn plain_number_is_added_to_note() {
+        let mut result = ExampleResult::default();
+        apply(&mut result, Some("12"));
+
+        assert_eq!(result.house_num.as_deref(), Some("12"));
+        assert_eq!(result.note.as_deref(), Some("12"));
+    }
+
+    #[test]
+    fn unparsed_street_number_is_kept_as_is() {
+        let mut result = ExampleResult::default();
+        apply(&mut result, Some("Lô B2"));
+
+        assert_eq!(result.house_num.as_deref(), Some("Lô B2"));
+        assert_eq!(result.alley, None);
+        assert_eq!(result.note.as_deref(), Some("Lô B2"));
+    }
+
+    #[test]
+    fn split_number_keeps_raw_value_in_note() {
+        let mut result = ExampleResult {
+            note: Some("SĐT khác: 0281234567".to_string()),
+            ..Default::default()
+        };
+        apply(&mut result, Some("12/3A"));
+
+        assert_eq!(result.house_num.as_deref(), Some("3A"));
+        assert_eq!(result.alley.as_deref(), Some("12"));
+        assert_eq!(result.note.as_deref(), Some("SĐT khác: 0281234567; 12/3A"));
+    }
+}
@@ -1,12 +1,36 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod address;
+mod admin;
+mod boundary;
+mod brand;
+mod cache;
+mod contact;
+mod dedup;
+mod export;
+mod history;
+mod house_number;
+mod phone;
+mod provider;
+mod type_mapping;
+mod upstream;
+
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use futures::stream::{self, StreamExt};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::thread;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use tiny_http::{Header, Method, Response, Server};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use axum::{
+    Router,
+    body::Bytes,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
 use tauri::{
     Emitter,
     Manager,
@@ -17,6 +41,18 @@ use tauri::{
 use reqwest;
 use chrono::{DateTime, FixedOffset, Utc};
 use chrono::offset::TimeZone;
+use address::AddressNormalizer;
+use admin::AdminUnits;
+use boundary::{BoundaryIndex, BoundaryProperties};
+use brand::BrandDictionary;
+use phone::PhoneFormat;
+use cache::ResponseCache;
+use dedup::{DupIndex, DupRecord};
+use export::ExportFormat;
+use history::{HistoryDb, HistoryEntry, HistoryRecord, HistorySummary};
+use type_mapping::{TypeMapping, TypeMappingInfo};
+use upstream::RetryPolicy;
+use provider::{GeocodeProvider, ProviderConfig, ProviderContext, ProviderKind};
 
 #[derive(Deserialize)]
 struct IncomingData {
@@ -24,6 +60,28 @@ struct IncomingData {
     lng: f64,
 }
 
+// Request tìm địa điểm theo địa chỉ hoặc tên (POST /search)
+#[derive(Deserialize)]
+struct SearchRequest {
+    query: String,
+}
+
+// Một điểm trong request batch, kèm ID feature do add-in gửi lên
+#[derive(Deserialize)]
+struct BatchItem {
+    id: String,
+    lat: f64,
+    lng: f64,
+}
+
+// Kết quả trả về cho từng điểm trong batch
+#[derive(Serialize)]
+struct BatchResult {
+    id: String,
+    #[serde(flatten)]
+    result: ExampleResult,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct MapViewRequest {
     lat: f64,
@@ -59,6 +117,11 @@ pub struct ExampleResult {
     pub room: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub house_num: Option<String>,
+    // Hẻm/ngõ/kiệt và ngách tách từ số nhà
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alley: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_alley: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub buaname: Option<String>,
 
@@ -67,6 +130,14 @@ pub struct ExampleResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sub_com: Option<String>,
 
+    // Tỉnh/thành phố, quận/huyện, phường/xã
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub province: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub district: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ward: Option<String>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub phone: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -75,6 +146,21 @@ pub struct ExampleResult {
     pub web: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mail: Option<String>,
+    // Link trang mạng xã hội (Facebook, Zalo, ...) tách khỏi web
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub social: Option<String>,
+
+    // Thông tin thêm từ place details (chọn field qua place_details_fields)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub intl_phone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opening_hours: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub business_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating_count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub map_url: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub brandname: Option<String>,
@@ -115,6 +201,44 @@ pub struct ExampleResult {
     pub latitude: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub longitude: Option<f64>,
+
+    // Khoảng cách (mét) từ điểm click tới địa điểm khớp / candidate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
+
+    // "hit" nếu toàn bộ dữ liệu lấy từ cache, "miss" nếu có gọi upstream
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<String>,
+
+    // Danh sách POI gần điểm click, sắp theo khoảng cách (chỉ có khi bật candidate_mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub candidates: Option<Vec<Candidate>>,
+
+    // Id của request đang chờ chọn candidate, chỉ gửi cho widget (confirm/cancel theo id)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selection_id: Option<u64>,
+}
+
+// Một POI ứng viên quanh điểm click
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Candidate {
+    pub rank: usize,
+    #[serde(flatten)]
+    pub result: ExampleResult,
+}
+
+// Id tăng dần cho các request chờ chọn candidate
+static NEXT_SELECTION_ID: AtomicU64 = AtomicU64::new(1);
+
+// Request /process đang chờ editor chọn candidate trên widget
+struct PendingSelection {
+    id: u64,
+    lat: f64,
+    lng: f64,
+    result: ExampleResult,
+    // Dòng lịch sử đã ghi cho request, cập nhật khi có kết quả chọn
+    history_id: Option<i64>,
+    sender: tokio::sync::oneshot::Sender<ExampleResult>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -147,19 +271,325 @@ impl Default for MapConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ApiConfig {
     base_url: String,
+    // Provider dùng cho base_url (google, nominatim, goong) và API key nếu provider yêu cầu
+    #[serde(default)]
+    provider: ProviderKind,
+    #[serde(default)]
+    api_key: String,
+    // Danh sách provider thử lần lượt (rỗng: chỉ dùng provider/base_url/api_key ở trên)
+    #[serde(default)]
+    providers: Vec<ProviderConfig>,
     opacity: f64,
     maps: MapConfig,
     default_perform: String,
+    // Số điểm xử lý đồng thời cho /process/batch
+    #[serde(default = "default_batch_concurrency")]
+    batch_concurrency: usize,
+    // Địa chỉ và cổng server cho add-in (tự chuyển sang cổng kế tiếp nếu bị chiếm)
+    #[serde(default = "default_listen_host")]
+    listen_host: String,
+    #[serde(default = "default_listen_port")]
+    listen_port: u16,
+    // Cache response geocode/placedetails (ttl = 0: không hết hạn, max = 0: tắt cache)
+    #[serde(default = "default_cache_ttl_hours")]
+    cache_ttl_hours: u64,
+    #[serde(default = "default_cache_max_entries")]
+    cache_max_entries: usize,
+    // Timeout và retry (exponential backoff) cho request tới upstream
+    #[serde(default = "default_connect_timeout_ms")]
+    connect_timeout_ms: u64,
+    #[serde(default = "default_read_timeout_ms")]
+    read_timeout_ms: u64,
+    #[serde(default = "default_max_retries")]
+    max_retries: u32,
+    #[serde(default = "default_retry_backoff_ms")]
+    retry_backoff_ms: u64,
+    // Tìm establishment thật tại điểm click qua /nearbysearch (bán kính tính bằng mét)
+    #[serde(default)]
+    nearby_search: bool,
+    #[serde(default = "default_nearby_radius_m")]
+    nearby_radius_m: u32,
+    // Khoảng cách tối đa (mét) giữa điểm click và địa điểm để coi là khớp,
+    // có thể đặt riêng theo sub_type (công viên, sân bay, ... cần bán kính lớn hơn).
+    // Key kiểu cũ theo Google type ("park") được đổi sang sub_type khi load
+    // (migrate_type_match_distance)
+    #[serde(default = "default_match_distance_m")]
+    match_distance_m: f64,
+    #[serde(default)]
+    type_match_distance_m: HashMap<String, f64>,
+    // Lấy tên tiếng Anh (poi_en) bằng request place details thứ hai với language=en.
+    // Tắt mặc định vì tốn thêm một request placedetails cho mỗi điểm
+    #[serde(default = "default_english_name")]
+    english_name: bool,
+    // Các field place details được ghi vào kết quả (international_phone_number,
+    // opening_hours, business_status, user_ratings_total, url)
+    #[serde(default = "default_place_details_fields")]
+    place_details_fields: Vec<String>,
+    // Format số điện thoại/fax: local ("028 3823 4567"), e164 ("+842838234567"), international
+    #[serde(default)]
+    phone_format: PhoneFormat,
+    // Tìm email trên website (trang chủ, trang liên hệ) khi provider không trả mail,
+    // giới hạn tổng thời gian và dung lượng đọc mỗi trang
+    #[serde(default)]
+    fetch_contact_email: bool,
+    #[serde(default = "default_contact_fetch_timeout_ms")]
+    contact_fetch_timeout_ms: u64,
+    #[serde(default = "default_contact_fetch_max_bytes")]
+    contact_fetch_max_bytes: usize,
+    // File ranh giới phường/xã: GeoJSON hoặc Shapefile .shp/.dbf
+    // (để trống: boundaries.geojson trong thư mục config)
+    #[serde(default)]
+    boundary_file: String,
+    #[serde(default)]
+    boundary_properties: BoundaryProperties,
+    // Ghi đè province/district/ward của upstream bằng kết quả tra ranh giới offline
+    #[serde(default = "default_boundary_override")]
+    boundary_override: bool,
+    // Đánh dấu dup khi đã có điểm trước đó cùng google_id, hoặc tên gần giống
+    // (độ giống >= dup_name_similarity) trong bán kính dup_radius_m
+    #[serde(default = "default_dup_detection")]
+    dup_detection: bool,
+    #[serde(default = "default_dup_radius_m")]
+    dup_radius_m: f64,
+    #[serde(default = "default_dup_name_similarity")]
+    dup_name_similarity: f64,
+    // Trả về nhiều POI ứng viên và chờ editor chọn trên widget trước khi trả cho add-in
+    #[serde(default)]
+    candidate_mode: bool,
+    #[serde(default = "default_max_candidates")]
+    max_candidates: usize,
+    #[serde(default = "default_candidate_timeout_secs")]
+    candidate_timeout_secs: u64,
+}
+
+fn default_batch_concurrency() -> usize {
+    4
+}
+
+fn default_listen_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_listen_port() -> u16 {
+    31203
+}
+
+fn default_cache_ttl_hours() -> u64 {
+    24 * 30
+}
+
+fn default_cache_max_entries() -> usize {
+    5000
+}
+
+fn default_connect_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_read_timeout_ms() -> u64 {
+    15000
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+fn default_retry_backoff_ms() -> u64 {
+    500
+}
+
+fn default_nearby_radius_m() -> u32 {
+    30
+}
+
+fn default_match_distance_m() -> f64 {
+    50.0
+}
+
+fn default_english_name() -> bool {
+    false
+}
+
+fn default_place_details_fields() -> Vec<String> {
+    ["international_phone_number", "opening_hours", "business_status", "user_ratings_total", "url"]
+        .iter()
+        .map(|f| f.to_string())
+        .collect()
+}
+
+fn default_contact_fetch_timeout_ms() -> u64 {
+    3000
+}
+
+fn default_contact_fetch_max_bytes() -> usize {
+    256 * 1024
+}
+
+fn default_boundary_override() -> bool {
+    true
+}
+
+fn default_dup_detection() -> bool {
+    true
+}
+
+fn default_dup_radius_m() -> f64 {
+    30.0
+}
+
+fn default_dup_name_similarity() -> f64 {
+    0.8
+}
+
+fn default_max_candidates() -> usize {
+    5
+}
+
+fn default_candidate_timeout_secs() -> u64 {
+    120
+}
+
+// Số cổng thử tiếp theo khi cổng cấu hình đã bị chiếm
+const PORT_FALLBACK_ATTEMPTS: u16 = 20;
+
+// Chu kỳ ghi cache và dup index xuống file (giây)
+const FLUSH_INTERVAL_SECS: u64 = 30;
+
+// Thông tin server thực tế, trả về qua GET /discovery và ghi ra file server.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ServerInfo {
+    service: String,
+    version: String,
+    host: String,
+    port: u16,
+    pid: u32,
 }
 
 impl Default for ApiConfig {
     fn default() -> Self {
         Self {
             base_url: "".to_string(),
+            provider: ProviderKind::default(),
+            api_key: "".to_string(),
+            providers: Vec::new(),
             opacity: 0.8,
             maps: MapConfig::default(),
             default_perform: "".to_string(),
+            batch_concurrency: default_batch_concurrency(),
+            listen_host: default_listen_host(),
+            listen_port: default_listen_port(),
+            cache_ttl_hours: default_cache_ttl_hours(),
+            cache_max_entries: default_cache_max_entries(),
+            connect_timeout_ms: default_connect_timeout_ms(),
+            read_timeout_ms: default_read_timeout_ms(),
+            max_retries: default_max_retries(),
+            retry_backoff_ms: default_retry_backoff_ms(),
+            nearby_search: false,
+            nearby_radius_m: default_nearby_radius_m(),
+            match_distance_m: default_match_distance_m(),
+            type_match_distance_m: HashMap::new(),
+            english_name: default_english_name(),
+            place_details_fields: default_place_details_fields(),
+            phone_format: PhoneFormat::default(),
+            fetch_contact_email: false,
+            contact_fetch_timeout_ms: default_contact_fetch_timeout_ms(),
+            contact_fetch_max_bytes: default_contact_fetch_max_bytes(),
+            boundary_file: String::new(),
+            boundary_properties: BoundaryProperties::default(),
+            boundary_override: default_boundary_override(),
+            dup_detection: default_dup_detection(),
+            dup_radius_m: default_dup_radius_m(),
+            dup_name_similarity: default_dup_name_similarity(),
+            candidate_mode: false,
+            max_candidates: default_max_candidates(),
+            candidate_timeout_secs: default_candidate_timeout_secs(),
+        }
+    }
+}
+
+impl ApiConfig {
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_retries: self.max_retries,
+            backoff_ms: self.retry_backoff_ms,
+        }
+    }
+
+    fn build_http_client(&self) -> reqwest::Client {
+        upstream::build_client(self.connect_timeout_ms, self.read_timeout_ms)
+    }
+
+    // Proxy Google bắt buộc có base_url, Nominatim/Goong có URL mặc định
+    fn is_configured(&self) -> bool {
+        self.provider_chain()
+            .iter()
+            .any(|p| p.kind != ProviderKind::Google || !p.base_url.is_empty())
+    }
+
+    fn primary_provider(&self) -> ProviderConfig {
+        ProviderConfig {
+            kind: self.provider,
+            base_url: self.base_url.clone(),
+            api_key: self.api_key.clone(),
+        }
+    }
+
+    // Thứ tự provider để thử; provider Google không khai báo URL thì dùng base_url chung
+    fn provider_chain(&self) -> Vec<ProviderConfig> {
+        if self.providers.is_empty() {
+            return vec![self.primary_provider()];
+        }
+
+        self.providers
+            .iter()
+            .cloned()
+            .map(|mut p| {
+                if p.kind == ProviderKind::Google && p.base_url.is_empty() {
+                    p.base_url = self.base_url.clone();
+                }
+                p
+            })
+            .collect()
+    }
+
+    // Khoảng cách khớp cho một sub_type, không có cấu hình riêng thì dùng match_distance_m
+    fn match_distance_for(&self, sub_type: Option<&str>) -> f64 {
+        sub_type
+            .and_then(|t| self.type_match_distance_m.get(t))
+            .copied()
+            .unwrap_or(self.match_distance_m)
+    }
+
+    // Đổi key type_match_distance_m theo Google type (config cũ) sang sub_type theo bảng mapping.
+    // Trả về true nếu có thay đổi cần ghi lại config
+    fn migrate_type_match_distance(&mut self, mapping: &TypeMapping) -> bool {
+        let mut changed = false;
+        let mut migrated = HashMap::new();
+
+        for (key, distance) in std::mem::take(&mut self.type_match_distance_m) {
+            // Key đã là sub_type
+            if mapping.category_of(&key).is_some() {
+                migrated.insert(key, distance);
+                continue;
+            }
+
+            match mapping.map_types([key.as_str()]).sub_type {
+                Some(sub_type) => {
+                    println!("type_match_distance_m: {} -> {}", key, sub_type);
+                    // Nhiều Google type cùng sub_type thì giữ khoảng cách lớn nhất
+                    let entry = migrated.entry(sub_type).or_insert(distance);
+                    *entry = entry.max(distance);
+                    changed = true;
+                }
+                None => {
+                    println!("type_match_distance_m: \"{}\" không phải sub_type và không map được, giữ nguyên", key);
+                    migrated.insert(key, distance);
+                }
+            }
         }
+
+        self.type_match_distance_m = migrated;
+        changed
     }
 }
 
@@ -168,9 +598,27 @@ impl Default for ApiConfig {
 struct AppState {
     window: Arc<Mutex<Option<tauri::WebviewWindow>>>,
     latest_data: Arc<Mutex<Option<ExampleResult>>>,
-    pending_requests: Arc<Mutex<Vec<tokio::sync::oneshot::Sender<ExampleResult>>>>,
+    pending_requests: Arc<Mutex<Vec<PendingSelection>>>,
     api_config: Arc<Mutex<ApiConfig>>,
-    is_processing: Arc<Mutex<bool>>,
+    // Số request đang xử lý (axum chạy đồng thời nhiều request)
+    processing_count: Arc<Mutex<usize>>,
+    server_info: Arc<Mutex<Option<ServerInfo>>>,
+    cache: Arc<ResponseCache>,
+    // Các điểm đã xử lý/import để phát hiện POI bị số hóa trùng
+    dup_index: Arc<DupIndex>,
+    // Lịch sử mọi lần /process (SQLite)
+    history: Arc<HistoryDb>,
+    http_client: Arc<Mutex<reqwest::Client>>,
+    // Bảng Google type -> sub_type, thay cả Arc khi reload
+    type_mapping: Arc<Mutex<Arc<TypeMapping>>>,
+    // Rule chuẩn hóa địa chỉ tiếng Việt
+    address_rules: Arc<Mutex<Arc<AddressNormalizer>>>,
+    // Danh mục đơn vị hành chính offline
+    admin_units: Arc<Mutex<Arc<AdminUnits>>>,
+    // Danh mục thương hiệu để nhận diện brandname từ tên POI
+    brands: Arc<Mutex<Arc<BrandDictionary>>>,
+    // Chỉ mục ranh giới hành chính (R-tree) cho tra cứu offline theo tọa độ
+    boundaries: Arc<Mutex<Arc<BoundaryIndex>>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -307,543 +755,1241 @@ fn get_config_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
     Ok(path)
 }
 
-// Hàm gọi Geocode API để lấy thông tin cơ bản và place_id
-async fn call_geocode_api(lat: f64, lng: f64, base_url: &str) -> Result<ExampleResult, Box<dyn std::error::Error>> {
-    let url = format!("{}/geocode?latlng={},{}", base_url, lat, lng);
-    println!("Calling Geocode API: {}", url);
+// Hàm lấy đường dẫn file server.json (nằm cạnh config.json) để add-in tìm cổng thực tế
+fn get_server_info_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut path = get_config_path()?;
+    path.set_file_name("server.json");
+    Ok(path)
+}
+
+// Hàm lấy đường dẫn file type_mapping.json (có thể thay bằng type_mapping.csv cùng thư mục)
+fn get_type_mapping_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut path = get_config_path()?;
+    path.set_file_name("type_mapping.json");
+    Ok(path)
+}
+
+// Hàm lấy đường dẫn file brands.json
+fn get_brands_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut path = get_config_path()?;
+    path.set_file_name("brands.json");
+    Ok(path)
+}
+
+// Hàm lấy đường dẫn file address_rules.json
+fn get_address_rules_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut path = get_config_path()?;
+    path.set_file_name("address_rules.json");
+    Ok(path)
+}
+
+// Hàm lấy đường dẫn file admin_units.json (danh mục tỉnh/quận/phường, không bắt buộc)
+fn get_admin_units_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut path = get_config_path()?;
+    path.set_file_name("admin_units.json");
+    Ok(path)
+}
+
+// Hàm lấy đường dẫn file ranh giới: boundary_file nếu có, mặc định boundaries.geojson
+fn get_boundary_path(config: &ApiConfig) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    if !config.boundary_file.is_empty() {
+        return Ok(PathBuf::from(&config.boundary_file));
+    }
+
+    let mut path = get_config_path()?;
+    path.set_file_name("boundaries.geojson");
+    Ok(path)
+}
+
+// Hàm lấy đường dẫn file dup_index.json
+fn get_dup_index_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut path = get_config_path()?;
+    path.set_file_name("dup_index.json");
+    Ok(path)
+}
+
+// Hàm lấy đường dẫn file history.db
+fn get_history_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut path = get_config_path()?;
+    path.set_file_name("history.db");
+    Ok(path)
+}
+
+// Hàm lấy đường dẫn thư mục exports (cạnh config.json)
+fn get_exports_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut path = get_config_path()?;
+    path.set_file_name("exports");
+    Ok(path)
+}
+
+// Hàm lấy đường dẫn file cache.json
+fn get_cache_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut path = get_config_path()?;
+    path.set_file_name("cache.json");
+    Ok(path)
+}
+
+// Hàm ghi thông tin server ra file
+fn save_server_info(info: &ServerInfo) -> Result<(), Box<dyn std::error::Error>> {
+    let info_path = get_server_info_path()?;
+    let info_dir = info_path.parent().unwrap();
+
+    if !info_dir.exists() {
+        fs::create_dir_all(info_dir)?;
+    }
+
+    let info_json = serde_json::to_string_pretty(info)?;
+    fs::write(&info_path, info_json)?;
+    println!("Server info saved to: {:?}", info_path);
+    Ok(())
+}
+
+// Thử reverse geocode lần lượt theo provider_chain, dừng ở provider đầu tiên
+// trả về kết quả dùng được (status "A"); trả về cả provider đã trả lời
+async fn reverse_geocode_with_fallback(
+    lat: f64,
+    lng: f64,
+    config: &ApiConfig,
+    ctx: &ProviderContext<'_>,
+) -> Result<(Box<dyn GeocodeProvider>, ExampleResult), Box<dyn std::error::Error + Send + Sync>> {
+    let mut failures: Vec<String> = Vec::new();
+    let mut last_unusable: Option<(Box<dyn GeocodeProvider>, ExampleResult)> = None;
+
+    for provider_config in config.provider_chain() {
+        if provider_config.kind == ProviderKind::Google && provider_config.base_url.is_empty() {
+            continue;
+        }
+
+        let provider = provider::build_provider(&provider_config);
+
+        match provider.reverse_geocode(ctx, lat, lng).await {
+            Ok(mut result) => {
+                result.source = Some(provider.source().to_string());
+
+                if result.status == "A" {
+                    if !failures.is_empty() {
+                        println!("Fallback to {} after: {}", provider.source(), failures.join("; "));
+                    }
+                    return Ok((provider, result));
+                }
+
+                let reason = result.status_detail.clone().unwrap_or_else(|| "no result".to_string());
+                println!("Provider {} returned no usable result: {}", provider.source(), reason);
+                failures.push(format!("{}: {}", provider.source(), reason));
+                last_unusable = Some((provider, result));
+            }
+            Err(e) => {
+                println!("Provider {} failed: {}", provider.source(), e);
+                failures.push(format!("{}: {}", provider.source(), e));
+            }
+        }
+    }
+
+    match last_unusable {
+        Some((provider, mut result)) => {
+            // Chỉ có một provider thì giữ nguyên status_detail của nó
+            if failures.len() > 1 {
+                result.status_detail = Some(failures.join("; "));
+            }
+            Ok((provider, result))
+        }
+        None if failures.is_empty() => Err("Base URL not configured".into()),
+        None => Err(failures.join("; ").into()),
+    }
+}
+
+// Tạo ProviderContext từ cấu hình và state hiện tại
+fn provider_context<'a>(config: &ApiConfig, app_state: &'a AppState) -> ProviderContext<'a> {
+    ProviderContext {
+        client: current_http_client(app_state),
+        retry: config.retry_policy(),
+        cache: &app_state.cache,
+        cache_ttl_hours: config.cache_ttl_hours,
+        cache_max_entries: config.cache_max_entries,
+        type_mapping: current_type_mapping(app_state),
+        responses: Mutex::new(serde_json::Map::new()),
+    }
+}
+
+// Gọi place details cho google_id của result và gộp thông tin chi tiết vào result
+async fn apply_place_details(
+    lat: f64,
+    lng: f64,
+    config: &ApiConfig,
+    provider: &dyn GeocodeProvider,
+    ctx: &ProviderContext<'_>,
+    result: &mut ExampleResult,
+) {
+    let Some(place_id) = result.google_id.clone() else {
+        return;
+    };
+
+    match provider.place_details(ctx, &place_id).await {
+        Ok(details) => {
+            // Chỉ báo "hit" khi cả geocode và placedetails đều lấy từ cache
+            if details.cache.as_deref() != Some("hit") {
+                result.cache = details.cache.clone();
+            }
+
+            // --- KIỂM TRA KHOẢNG CÁCH ---
+            if let (Some(detail_lat), Some(detail_lng)) = (details.latitude, details.longitude) {
+                let distance = haversine_distance(lat, lng, detail_lat, detail_lng);
+                println!("Distance between original ({},{}) and place details ({},{}): {:.2} meters",
+                         lat, lng, detail_lat, detail_lng, distance);
+                result.distance = Some((distance * 10.0).round() / 10.0);
+
+                let max_distance = config.match_distance_for(details.sub_type.as_deref().or(result.sub_type.as_deref()));
+                if distance > max_distance {
+                    result.status = "D".to_string();
+                    result.status_detail = Some(format!("Không có điểm phù hợp (khoảng cách {:.1}m > {}m)", distance, max_distance));
+                }
+            } else {
+                println!("Warning: Place Details does not contain coordinates");
+            }
+            apply_place_details_extras(config, &details, result);
+            if let Some(poi_vn) = details.poi_vn {
+                result.poi_vn = Some(poi_vn);
+            }
+            if let Some(poi_en) = details.poi_en {
+                result.poi_en = Some(poi_en);
+            }
+            if let Some(phone) = details.phone {
+                result.phone = Some(phone);
+            }
+            if let Some(web) = details.web {
+                result.web = Some(web);
+            }
+            // Thêm parse cho type và sub_type
+            if let Some(type_val) = details.r#type {
+                result.r#type = Some(type_val);
+            }
+            if let Some(sub_type) = details.sub_type {
+                result.sub_type = Some(sub_type);
+            }
+            // Lỗi Places API hoặc type chưa map được, cần editor kiểm tra
+            if let Some(detail) = details.status_detail {
+                append_status_detail(result, &detail);
+            }
+        }
+        Err(e) => {
+            println!("Error calling placedetails API: {}", e);
+            result.status_detail = Some(format!("Placedetails API error: {}", e));
+        }
+    }
+}
+
+// Ghi các field place details bổ sung theo place_details_fields của config
+fn apply_place_details_extras(config: &ApiConfig, details: &ExampleResult, result: &mut ExampleResult) {
+    let wants = |field: &str| config.place_details_fields.iter().any(|f| f == field);
+
+    if wants("international_phone_number") && details.intl_phone.is_some() {
+        result.intl_phone = details.intl_phone.clone();
+    }
+    if wants("opening_hours") && details.opening_hours.is_some() {
+        result.opening_hours = details.opening_hours.clone();
+    }
+    if wants("user_ratings_total") && details.rating_count.is_some() {
+        result.rating_count = details.rating_count;
+    }
+    if wants("url") && details.map_url.is_some() {
+        result.map_url = details.map_url.clone();
+    }
+
+    if !wants("business_status") {
+        return;
+    }
+    let Some(business_status) = details.business_status.clone() else {
+        return;
+    };
+
+    match business_status.as_str() {
+        "CLOSED_PERMANENTLY" => {
+            result.status = "D".to_string();
+            append_status_detail(result, "Địa điểm đã đóng cửa vĩnh viễn (CLOSED_PERMANENTLY)");
+        }
+        "CLOSED_TEMPORARILY" => append_status_detail(result, "Địa điểm tạm đóng cửa (CLOSED_TEMPORARILY)"),
+        _ => {}
+    }
+    result.business_status = Some(business_status);
+}
+
+// Điền poi_en bằng place details language=en. Bỏ qua request khi provider đã trả
+// tên tiếng Anh, hoặc khi poi_vn chỉ gồm ký tự ASCII: đoán là tên hai ngôn ngữ giống nhau
+// (heuristic, tên không dấu vẫn có thể có bản tiếng Anh khác)
+async fn apply_english_name(
+    provider: &dyn GeocodeProvider,
+    ctx: &ProviderContext<'_>,
+    result: &mut ExampleResult,
+) {
+    if result.poi_en.is_some() {
+        return;
+    }
+
+    let (Some(poi_vn), Some(place_id)) = (result.poi_vn.clone(), result.google_id.clone()) else {
+        return;
+    };
+
+    if poi_vn.is_ascii() {
+        result.poi_en = Some(poi_vn);
+        return;
+    }
+
+    match provider.localized_name(ctx, &place_id, "en").await {
+        Ok(Some(name)) => result.poi_en = Some(name),
+        Ok(None) => {}
+        Err(e) => println!("Error getting English name: {}", e),
+    }
+}
+
+// Thêm ghi chú vào status_detail, giữ lại ghi chú đã có
+fn append_status_detail(result: &mut ExampleResult, detail: &str) {
+    result.status_detail = Some(match result.status_detail.take() {
+        Some(existing) if !existing.is_empty() => format!("{}; {}", existing, detail),
+        _ => detail.to_string(),
+    });
+}
+
+// Thêm ghi chú vào note (nối bằng "; " nếu đã có)
+fn append_note(result: &mut ExampleResult, note: &str) {
+    result.note = Some(match result.note.take() {
+        Some(existing) if !existing.is_empty() => format!("{}; {}", existing, note),
+        _ => note.to_string(),
+    });
+}
+
+// Điền mail bằng email tìm thấy trên website (bỏ qua khi đã có mail hoặc web là link mạng xã hội)
+async fn apply_contact_email(config: &ApiConfig, ctx: &ProviderContext<'_>, result: &mut ExampleResult) {
+    if result.mail.is_some() {
+        return;
+    }
+
+    let Some(website) = result.web.as_deref().and_then(contact::clean_url).filter(|url| !contact::is_social(url)) else {
+        return;
+    };
+
+    let Some(domain) = contact::domain(&website) else {
+        return;
+    };
+
+    // Cache cả kết quả "không có email" để không tải lại website mỗi lần click
+    let key = cache::contact_key(&domain);
+    if let Some(value) = ctx.cache.get(&key, ctx.cache_ttl_hours) {
+        println!("Cache hit: {}", key);
+        result.mail = value["email"].as_str().map(|email| email.to_string());
+        return;
+    }
+
+    match contact::fetch_email(&ctx.client, &website, config.contact_fetch_timeout_ms, config.contact_fetch_max_bytes, ctx.retry).await {
+        Ok(email) => {
+            ctx.cache.insert(key, serde_json::json!({ "email": email }), ctx.cache_max_entries);
+            result.mail = email;
+        }
+        Err(e) => println!("Error fetching contact email for {}: {}", website, e),
+    }
+}
+
+// Tìm establishment gần điểm click nhất (trong khoảng cách khớp theo type) qua
+// nearby search và gộp tên, type, place_id của nó vào result
+async fn apply_nearby_search(
+    lat: f64,
+    lng: f64,
+    config: &ApiConfig,
+    provider: &dyn GeocodeProvider,
+    ctx: &ProviderContext<'_>,
+    result: &mut ExampleResult,
+) {
+    let places = match provider.nearby_search(ctx, lat, lng, config.nearby_radius_m).await {
+        Ok(places) => places,
+        Err(e) => {
+            println!("Error calling nearbysearch API: {}", e);
+            return;
+        }
+    };
+
+    let nearest = places
+        .into_iter()
+        .filter_map(|place| {
+            let (p_lat, p_lng) = (place.latitude?, place.longitude?);
+            Some((haversine_distance(lat, lng, p_lat, p_lng), place))
+        })
+        .filter(|(distance, place)| *distance <= config.match_distance_for(place.sub_type.as_deref()))
+        .min_by(|(a, _), (b, _)| a.total_cmp(b));
+
+    let Some((distance, place)) = nearest else {
+        println!("Nearbysearch: no establishment within match distance");
+        return;
+    };
+
+    println!("Nearbysearch matched {:?} at {:.2} meters", place.poi_vn, distance);
+
+    if place.poi_vn.is_some() {
+        result.poi_vn = place.poi_vn;
+    }
+    if place.r#type.is_some() {
+        result.r#type = place.r#type;
+    }
+    if place.sub_type.is_some() {
+        result.sub_type = place.sub_type;
+    }
+    // Dùng place_id của establishment cho bước place details
+    if place.google_id.is_some() {
+        result.google_id = place.google_id;
+    }
+}
+
+// Lấy các POI ứng viên quanh điểm click, bỏ trùng google_id và sắp theo khoảng cách
+async fn collect_candidates(
+    lat: f64,
+    lng: f64,
+    provider: &dyn GeocodeProvider,
+    ctx: &ProviderContext<'_>,
+    max_candidates: usize,
+) -> Vec<Candidate> {
+    let results = match provider.reverse_geocode_candidates(ctx, lat, lng).await {
+        Ok(results) => results,
+        Err(e) => {
+            println!("Error getting candidates: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut seen_ids: Vec<String> = Vec::new();
+    let mut candidates: Vec<Candidate> = Vec::new();
+
+    for mut result in results {
+        if let Some(id) = &result.google_id {
+            if seen_ids.contains(id) {
+                continue;
+            }
+            seen_ids.push(id.clone());
+        }
+
+        result.distance = match (result.latitude, result.longitude) {
+            (Some(c_lat), Some(c_lng)) => Some((haversine_distance(lat, lng, c_lat, c_lng) * 10.0).round() / 10.0),
+            _ => None,
+        };
+        result.source = Some(provider.source().to_string());
+
+        candidates.push(Candidate { rank: 0, result });
+    }
+
+    // Candidate không có tọa độ xếp cuối
+    candidates.sort_by(|a, b| {
+        a.result.distance
+            .unwrap_or(f64::MAX)
+            .total_cmp(&b.result.distance.unwrap_or(f64::MAX))
+    });
+    candidates.truncate(max_candidates.max(1));
+
+    for (i, candidate) in candidates.iter_mut().enumerate() {
+        candidate.rank = i + 1;
+    }
+
+    candidates
+}
+
+// Thêm ngày cập nhật (GMT+7) với định dạng dd/mm/yyyy
+fn update_date_gmt7() -> String {
+    let gmt_plus_7 = FixedOffset::east_opt(7 * 3600).unwrap(); // GMT+7
+    let now_utc = Utc::now();
+    let now_gmt7 = gmt_plus_7.from_utc_datetime(&now_utc.naive_utc());
+
+    now_gmt7.format("%d/%m/%Y").to_string()
+}
+
+// with_candidates = false (batch): không có editor chọn candidate nên dùng luôn kết quả khớp nhất,
+// chạy đủ boundary, chuẩn hóa và kiểm tra trùng như /process
+async fn call_custom_api(lat: f64, lng: f64, config: &ApiConfig, app_state: &AppState, ctx: &ProviderContext<'_>, with_candidates: bool) -> Result<ExampleResult, Box<dyn std::error::Error + Send + Sync>> {
+    let (provider, mut result) = reverse_geocode_with_fallback(lat, lng, config, ctx).await?;
+    
+    if !config.default_perform.is_empty() {
+        result.perform = Some(config.default_perform.clone());
+    }
+
+    if config.nearby_search {
+        apply_nearby_search(lat, lng, config, provider.as_ref(), ctx, &mut result).await;
+    }
+
+    complete_result(lat, lng, config, app_state, Some(provider.as_ref()), ctx, &mut result).await;
+
+    if config.candidate_mode && with_candidates {
+        let candidates = collect_candidates(lat, lng, provider.as_ref(), ctx, config.max_candidates).await;
+        // Chỉ cần editor chọn khi có từ 2 candidate trở lên
+        if candidates.len() > 1 {
+            result.candidates = Some(candidates);
+        }
+    }
+
+    // Khi còn chờ editor chọn candidate thì kiểm tra trùng sau khi chọn (confirm_candidate)
+    if result.candidates.is_none() {
+        apply_dup_check(app_state, config, lat, lng, &mut result);
+    }
+
+    result.update_ = Some(update_date_gmt7());
+
+    Ok(result)
+}
+
+// Các bước chung cho kết quả /process và candidate được editor chọn:
+// place details, tên tiếng Anh, email liên hệ, ranh giới hành chính và chuẩn hóa
+async fn complete_result(
+    lat: f64,
+    lng: f64,
+    config: &ApiConfig,
+    app_state: &AppState,
+    provider: Option<&dyn GeocodeProvider>,
+    ctx: &ProviderContext<'_>,
+    result: &mut ExampleResult,
+) {
+    if let Some(provider) = provider {
+        apply_place_details(lat, lng, config, provider, ctx, result).await;
+
+        if config.english_name {
+            apply_english_name(provider, ctx, result).await;
+        }
+    }
+
+    if config.fetch_contact_email {
+        apply_contact_email(config, ctx, result).await;
+    }
+
+    apply_boundary(app_state, config, lat, lng, result);
+    finalize_result(app_state, config, result);
+}
+
+// Forward geocoding: thử lần lượt theo provider_chain, dừng ở provider đầu tiên có kết quả
+async fn search_places_with_fallback(query: &str, config: &ApiConfig, app_state: &AppState) -> Result<Vec<ExampleResult>, Box<dyn std::error::Error + Send + Sync>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err("Query is empty".into());
+    }
+
+    let ctx = provider_context(config, app_state);
+    let mut failures: Vec<String> = Vec::new();
+
+    for provider_config in config.provider_chain() {
+        if provider_config.kind == ProviderKind::Google && provider_config.base_url.is_empty() {
+            continue;
+        }
+
+        let provider = provider::build_provider(&provider_config);
+
+        match provider.search(&ctx, query).await {
+            Ok(results) if !results.is_empty() => {
+                let update = update_date_gmt7();
+                let results = results
+                    .into_iter()
+                    .map(|mut result| {
+                        result.source = Some(provider.source().to_string());
+                        result.update_ = Some(update.clone());
+                        finalize_result(app_state, config, &mut result);
+                        if !config.default_perform.is_empty() {
+                            result.perform = Some(config.default_perform.clone());
+                        }
+                        result
+                    })
+                    .collect();
+                return Ok(results);
+            }
+            Ok(_) => {
+                println!("Provider {} found nothing for {:?}", provider.source(), query);
+            }
+            Err(e) => {
+                println!("Provider {} search failed: {}", provider.source(), e);
+                failures.push(format!("{}: {}", provider.source(), e));
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(Vec::new())
+    } else {
+        Err(failures.join("; ").into())
+    }
+}
+
+// Tìm provider trong provider_chain có source trùng với source của candidate
+fn provider_for_source(config: &ApiConfig, source: Option<&str>) -> Option<Box<dyn GeocodeProvider>> {
+    config
+        .provider_chain()
+        .iter()
+        .map(provider::build_provider)
+        .find(|p| Some(p.source()) == source)
+}
+
+// Đẩy result có candidates vào hàng chờ (kèm id), gửi lên widget và chờ editor chọn.
+// Hết thời gian chờ hoặc editor đóng popup thì trả về result ban đầu, bỏ candidates
+// (confirm_candidate tự cập nhật dòng lịch sử khi editor chọn)
+async fn wait_for_selection(
+    app_state: &AppState,
+    lat: f64,
+    lng: f64,
+    mut result: ExampleResult,
+    timeout_secs: u64,
+    history_id: Option<i64>,
+) -> ExampleResult {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let id = NEXT_SELECTION_ID.fetch_add(1, Ordering::Relaxed);
+
+    {
+        if let Ok(mut pending) = app_state.pending_requests.lock() {
+            pending.retain(|p| !p.sender.is_closed());
+            pending.push(PendingSelection {
+                id,
+                lat,
+                lng,
+                result: result.clone(),
+                history_id,
+                sender: tx,
+            });
+        }
+    }
+
+    let shown = ExampleResult {
+        selection_id: Some(id),
+        ..result.clone()
+    };
+
+    if let Ok(mut latest_data) = app_state.latest_data.lock() {
+        *latest_data = Some(shown.clone());
+    }
+
+    if let Ok(window_lock) = app_state.window.lock() {
+        if let Some(window) = &*window_lock {
+            let _ = window.emit("update-result", &shown);
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+
+    println!("Waiting for candidate selection {} ({}s)", id, timeout_secs);
+    match tokio::time::timeout(std::time::Duration::from_secs(timeout_secs), rx).await {
+        Ok(Ok(selected)) => selected,
+        outcome => {
+            if outcome.is_err() {
+                println!("Candidate selection {} timed out, returning original result", id);
+            } else {
+                println!("Candidate selection {} cancelled, returning original result", id);
+            }
+            if let Ok(mut pending) = app_state.pending_requests.lock() {
+                pending.retain(|p| p.id != id);
+            }
+            result.candidates = None;
+            update_history(app_state, history_id, serde_json::Value::Null, &result).await;
+            result
+        }
+    }
+}
+
+// Hàm xử lý nhiều điểm với số request đồng thời giới hạn, giữ nguyên thứ tự đầu vào
+async fn process_batch(items: Vec<BatchItem>, config: &ApiConfig, app_state: &AppState) -> Vec<BatchResult> {
+    let concurrency = config.batch_concurrency.max(1);
+
+    stream::iter(items)
+        .map(|item| async move {
+            let started = Instant::now();
+            let ctx = provider_context(config, app_state);
+            let result = if config.is_configured() {
+                call_custom_api(item.lat, item.lng, config, app_state, &ctx, false).await
+            } else {
+                Err("Base URL not configured".into())
+            };
+
+            let (result, error) = match result {
+                Ok(result) => (result, None),
+                Err(e) => {
+                    println!("Error processing batch item {}: {}", item.id, e);
+                    let result = ExampleResult {
+                        status: "D".into(),
+                        status_detail: Some(format!("API Error: {}", e)),
+                        latitude: Some(item.lat),
+                        longitude: Some(item.lng),
+                        ..Default::default()
+                    };
+                    (result, Some(e.to_string()))
+                }
+            };
+
+            record_history(app_state, item.lat, item.lng, started, &ctx, error.as_deref(), &result).await;
+
+            BatchResult { id: item.id, result }
+        })
+        .buffered(concurrency)
+        .collect::<Vec<_>>()
+        .await
+}
+
+// Ghi lần xử lý vào lịch sử, trả về id dòng mới (lỗi ghi chỉ log, không ảnh hưởng response).
+// INSERT SQLite là blocking nên chạy trên thread spawn_blocking, không chiếm worker của Tokio
+async fn record_history(
+    app_state: &AppState,
+    lat: f64,
+    lng: f64,
+    started: Instant,
+    ctx: &ProviderContext<'_>,
+    error: Option<&str>,
+    result: &ExampleResult,
+) -> Option<i64> {
+    let record = HistoryRecord {
+        lat,
+        lng,
+        duration_ms: started.elapsed().as_millis() as u64,
+        error: error.map(|e| e.to_string()),
+        raw: ctx.take_responses(),
+        result: result.clone(),
+    };
+
+    let history = app_state.history.clone();
+    match tokio::task::spawn_blocking(move || history.record(&record)).await {
+        Ok(Ok(id)) => Some(id),
+        Ok(Err(e)) => {
+            println!("Error writing history: {}", e);
+            None
+        }
+        Err(e) => {
+            println!("Error writing history: {}", e);
+            None
+        }
+    }
+}
+
+// Cập nhật dòng lịch sử với kết quả cuối cùng và response gốc gọi thêm sau khi ghi
+async fn update_history(app_state: &AppState, history_id: Option<i64>, raw: serde_json::Value, result: &ExampleResult) {
+    let Some(id) = history_id else {
+        return;
+    };
+
+    let history = app_state.history.clone();
+    let result = result.clone();
+    match tokio::task::spawn_blocking(move || history.update(id, &result, &raw)).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => println!("Error updating history {}: {}", id, e),
+        Err(e) => println!("Error updating history {}: {}", id, e),
+    }
+}
+
+// Đọc cấu hình hiện tại từ state
+fn current_config(app_state: &AppState) -> ApiConfig {
+    if let Ok(config_lock) = app_state.api_config.lock() {
+        config_lock.clone()
+    } else {
+        ApiConfig::default()
+    }
+}
+
+// Lấy HTTP client dùng chung (reqwest::Client clone chỉ tăng ref count)
+fn current_http_client(app_state: &AppState) -> reqwest::Client {
+    if let Ok(client) = app_state.http_client.lock() {
+        client.clone()
+    } else {
+        reqwest::Client::new()
+    }
+}
+
+// Lấy bảng type mapping đang dùng
+fn current_type_mapping(app_state: &AppState) -> Arc<TypeMapping> {
+    if let Ok(mapping) = app_state.type_mapping.lock() {
+        Arc::clone(&mapping)
+    } else {
+        Arc::new(TypeMapping::builtin())
+    }
+}
+
+// Đọc lại file type mapping, các request đang chạy vẫn dùng bảng cũ
+fn reload_type_mapping_file(app_state: &AppState) -> TypeMappingInfo {
+    let mapping = TypeMapping::load(get_type_mapping_path().ok());
+    let info = mapping.info();
+
+    if let Ok(mut current) = app_state.type_mapping.lock() {
+        *current = Arc::new(mapping);
+    }
+
+    info
+}
+
+// Lấy bộ rule chuẩn hóa địa chỉ đang dùng
+fn current_address_normalizer(app_state: &AppState) -> Arc<AddressNormalizer> {
+    if let Ok(rules) = app_state.address_rules.lock() {
+        Arc::clone(&rules)
+    } else {
+        Arc::new(AddressNormalizer::builtin())
+    }
+}
+
+fn current_brands(app_state: &AppState) -> Arc<BrandDictionary> {
+    if let Ok(brands) = app_state.brands.lock() {
+        Arc::clone(&brands)
+    } else {
+        Arc::new(BrandDictionary::builtin())
+    }
+}
+
+// Đọc lại file brands.json
+fn reload_brands_file(app_state: &AppState) {
+    let brands = BrandDictionary::load(get_brands_path().ok());
+
+    if let Ok(mut current) = app_state.brands.lock() {
+        *current = Arc::new(brands);
+    }
+}
+
+// Đọc lại file address_rules.json
+fn reload_address_rules_file(app_state: &AppState) {
+    let normalizer = AddressNormalizer::load(get_address_rules_path().ok());
+
+    if let Ok(mut current) = app_state.address_rules.lock() {
+        *current = Arc::new(normalizer);
+    }
+}
+
+// Lấy danh mục đơn vị hành chính đang dùng
+fn current_admin_units(app_state: &AppState) -> Arc<AdminUnits> {
+    if let Ok(units) = app_state.admin_units.lock() {
+        Arc::clone(&units)
+    } else {
+        Arc::new(AdminUnits::default())
+    }
+}
+
+// Đọc lại file admin_units.json
+fn reload_admin_units_file(app_state: &AppState) {
+    let units = AdminUnits::load(get_admin_units_path().ok());
+
+    if let Ok(mut current) = app_state.admin_units.lock() {
+        *current = Arc::new(units);
+    }
+}
+
+// Load chỉ mục ranh giới theo cấu hình, không có file thì chỉ mục rỗng
+fn load_boundaries(config: &ApiConfig) -> BoundaryIndex {
+    match get_boundary_path(config) {
+        Ok(path) => BoundaryIndex::load(&path, &config.boundary_properties),
+        Err(e) => {
+            println!("Error getting boundary path: {}", e);
+            BoundaryIndex::default()
+        }
+    }
+}
+
+// Đọc lại file ranh giới theo cấu hình hiện tại
+fn reload_boundaries(app_state: &AppState, config: &ApiConfig) {
+    let index = load_boundaries(config);
+
+    if let Ok(mut current) = app_state.boundaries.lock() {
+        *current = Arc::new(index);
+    }
+}
+
+// Tra phường/quận/tỉnh theo tọa độ trong dữ liệu ranh giới offline và ghi vào result
+// (ghi đè kết quả upstream nếu boundary_override, nếu không chỉ điền field còn thiếu)
+fn apply_boundary(app_state: &AppState, config: &ApiConfig, lat: f64, lng: f64, result: &mut ExampleResult) {
+    let boundaries = match app_state.boundaries.lock() {
+        Ok(boundaries) => Arc::clone(&boundaries),
+        Err(_) => return,
+    };
+
+    if boundaries.is_empty() {
+        return;
+    }
+
+    let Some(area) = boundaries.lookup(lat, lng) else {
+        println!("Boundary lookup: ({}, {}) is outside all boundaries", lat, lng);
+        return;
+    };
+
+    let fields = [
+        (&mut result.ward, &area.ward),
+        (&mut result.district, &area.district),
+        (&mut result.province, &area.province),
+    ];
 
-    let client = reqwest::Client::new();
-    let response = client.get(&url).send().await?;
-    
-    if !response.status().is_success() {
-        return Err(format!("Geocode API request failed with status: {}", response.status()).into());
+    for (field, value) in fields {
+        let Some(value) = value else {
+            continue;
+        };
+
+        if field.is_none() || config.boundary_override {
+            if field.as_deref().is_some_and(|old| old != value) {
+                println!("Boundary lookup overrides {:?} with {:?}", field, value);
+            }
+            *field = Some(value.clone());
+        }
     }
+}
 
-    let response_text = response.text().await?;
-    println!("Geocode API response: {}", response_text);
+// Ghi dup ("<id> (<khoảng cách>m)") nếu đã có điểm trùng trong dup index, sau đó thêm
+// điểm hiện tại vào index
+fn apply_dup_check(app_state: &AppState, config: &ApiConfig, lat: f64, lng: f64, result: &mut ExampleResult) {
+    if !config.dup_detection {
+        return;
+    }
 
-    // Parse response từ Google Geocoding API
-    let geocode_response: serde_json::Value = serde_json::from_str(&response_text)?;
-    
-    // Tạo ExampleResult từ dữ liệu geocode
-    let mut result = parse_google_geocoding_response(geocode_response);
-    
-    Ok(result)
-}
+    // id theo be_id, không có thì theo tọa độ (không dùng google_id: các điểm khác nhau
+    // cùng google_id phải được báo trùng chứ không bị coi là gửi lại cùng điểm)
+    let record = DupRecord {
+        id: result.be_id.clone().unwrap_or_else(|| dedup::point_id(lat, lng)),
+        google_id: result.google_id.clone(),
+        name: result.poi_vn.clone().or_else(|| result.poi_en.clone()),
+        lat,
+        lng,
+    };
 
-// Hàm gọi Placedetails API để lấy thêm thông tin chi tiết
-async fn call_placedetails_api(place_id: &str, base_url: &str) -> Result<ExampleResult, Box<dyn std::error::Error>> {
-    let url = format!("{}/placedetails?place_id={}", base_url, place_id);
-    println!("Calling Placedetails API: {}", url);
+    if let Some(found) = app_state.dup_index.find(&record, config.dup_radius_m, config.dup_name_similarity) {
+        println!("Possible duplicate of {} at {:.1}m", found.id, found.distance);
+        result.dup = Some(format!("{} ({:.1}m)", found.id, found.distance));
+    }
 
-    let client = reqwest::Client::new();
-    let response = client.get(&url).send().await?;
-    
-    if !response.status().is_success() {
-        return Err(format!("Placedetails API request failed with status: {}", response.status()).into());
+    // Chỉ lưu điểm có POI, bỏ qua kết quả không dùng được (status "D")
+    if result.status == "D" || (record.google_id.is_none() && record.name.is_none()) {
+        return;
     }
 
-    let response_text = response.text().await?;
-    println!("Placedetails API response: {}", response_text);
+    app_state.dup_index.insert(record);
+}
 
-    // Parse response từ Google Places Details API
-    let placedetails_response: serde_json::Value = serde_json::from_str(&response_text)?;
-    
-    // Tạo ExampleResult từ dữ liệu placedetails
-    let result = parse_placedetails_response(placedetails_response);
-    
-    Ok(result)
+// Chuẩn hóa địa chỉ, điền các cấp hành chính còn thiếu, chuẩn hóa liên hệ và nhận diện
+// thương hiệu (cho cả candidates)
+fn finalize_result(app_state: &AppState, config: &ApiConfig, result: &mut ExampleResult) {
+    let normalizer = current_address_normalizer(app_state);
+    let admin_units = current_admin_units(app_state);
+    let brands = current_brands(app_state);
+    let type_mapping = current_type_mapping(app_state);
+
+    normalizer.apply(result);
+    admin_units.fill_missing(result);
+    phone::apply(result, config.phone_format);
+    contact::apply(result);
+    brands.apply(result, &type_mapping);
+
+    if let Some(candidates) = result.candidates.as_mut() {
+        for candidate in candidates {
+            normalizer.apply(&mut candidate.result);
+            admin_units.fill_missing(&mut candidate.result);
+            phone::apply(&mut candidate.result, config.phone_format);
+            contact::apply(&mut candidate.result);
+            brands.apply(&mut candidate.result, &type_mapping);
+        }
+    }
 }
 
-pub fn parse_google_geocoding_response(response: Value) -> ExampleResult {
-    let mut result = ExampleResult {
-        status: "A".to_string(), 
-        address: "".to_string(),
-        poi_vn: None,
-        poi_en: None,
-        poi_ex: None,
-
-        r#type: None,
-        sub_type: None,
-        poi_st_sd: None,
-
-        room: None,
-        house_num: None,
-        buaname: None,
-
-        st_name: None,
-        sub_com: None,
-
-        phone: None,
-        fax: None,
-        web: None,
-        mail: None,
-
-        brandname: None,
-        import: None,
-        status_detail: None,
-        note: None,
-        done: None,
-        update_: None,
-        source: Some("Googlemap".to_string()),
-        gen_type: None,
-        perform: None,
-        
-        dup: None,
-        explain: Some("4".to_string()),
-        classify: Some("P".to_string()),
-        dtrend: None,
-        google_id: None,
-        be_id: None,
-        plus_code: None,
-        latitude: None,
-        longitude: None,
+// Tăng/giảm số request đang xử lý và báo cho widget (processing khi còn ít nhất một request)
+fn set_processing_state(app_state: &AppState, processing: bool) {
+    let processing = match app_state.processing_count.lock() {
+        Ok(mut count) => {
+            if processing {
+                *count += 1;
+            } else {
+                *count = count.saturating_sub(1);
+            }
+            *count > 0
+        }
+        Err(_) => processing,
     };
 
-    if let Some(status) = response["status"].as_str() {
-        if status != "OK" {
-            result.status = "D".to_string(); 
-            result.status_detail = Some(format!("Google API error: {}", status));
-            return result;
+    if let Ok(window_lock) = app_state.window.lock() {
+        if let Some(window) = &*window_lock {
+            let _ = window.emit("update-processing-state", processing);
         }
     }
+}
 
-    let first = &response["results"][0];
-
-    result.address = first["formatted_address"]
-        .as_str()
-        .unwrap_or_default()
-        .to_string();
+// Phản hồi JSON với header Content-Type giống server cũ
+fn json_response(body: String) -> Response {
+    ([(header::CONTENT_TYPE, "application/json")], body).into_response()
+}
 
-    if let Some(loc) = first["geometry"]["location"].as_object() {
-        result.latitude = loc.get("lat").and_then(|v| v.as_f64());
-        result.longitude = loc.get("lng").and_then(|v| v.as_f64());
+// Ghi các thay đổi của cache và dup index xuống file định kỳ (ngoài request, trên thread blocking)
+async fn flush_periodically(app_state: Arc<AppState>) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(FLUSH_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+        let app_state = Arc::clone(&app_state);
+        let _ = tokio::task::spawn_blocking(move || flush_state(&app_state)).await;
     }
+}
+
+fn flush_state(app_state: &AppState) {
+    app_state.cache.flush();
+    app_state.dup_index.flush();
+}
+
+// Handler cho POST /process
+async fn handle_process(State(app_state): State<Arc<AppState>>, body: Bytes) -> Response {
+    println!("Received request from Addin!");
+
+    let content = match String::from_utf8(body.to_vec()) {
+        Ok(content) => content,
+        Err(e) => {
+            println!("Error reading request body: {}", e);
+            return format!("Error reading body: {}", e).into_response();
+        }
+    };
+    println!("Raw data = {}", content);
 
-    result.plus_code = first["plus_code"]["global_code"]
-        .as_str()
-        .map(|s| s.to_string());
+    let parsed: IncomingData = match serde_json::from_str(&content) {
+        Ok(data) => data,
+        Err(e) => {
+            println!("Error parsing JSON: {}", e);
+            return format!("Error parsing JSON: {}", e).into_response();
+        }
+    };
+    println!("Lat = {}, Lon = {}", parsed.lat, parsed.lng);
 
-    if let Some(components) = first["address_components"].as_array() {
-        for comp in components {
-            let long = comp["long_name"].as_str().unwrap_or("").to_string();
-            let types = comp["types"].as_array().map_or(&[] as &[serde_json::Value], |v| v);
+    set_processing_state(&app_state, true);
 
-            let is = |t: &str| types.iter().any(|x| x.as_str() == Some(t));
+    let config = current_config(&app_state);
+    let started = Instant::now();
+    let ctx = provider_context(&config, &app_state);
 
-            if is("premise") || is("point_of_interest") {
-                result.poi_vn = Some(long.clone());
+    let result = if config.is_configured() {
+        call_custom_api(parsed.lat, parsed.lng, &config, &app_state, &ctx, true).await
+    } else {
+        Err("Base URL not configured".into())
+    };
+    let error = result.as_ref().err().map(|e| e.to_string());
+
+    let response_json = match result {
+        // Có candidates: wait_for_selection gửi lên widget kèm selection_id
+        Ok(result) if result.candidates.is_some() => result,
+        Ok(result) => {
+            if let Ok(mut latest_data) = app_state.latest_data.lock() {
+                *latest_data = Some(result.clone());
             }
-            if is("street_number") {
-                let mut house = long.clone();
-                // Loại bỏ tất cả các số 0 ở đầu
-                let trimmed = house.trim_start_matches('0');
-                if trimmed.is_empty() {
-                    // Trường hợp chuỗi chỉ toàn số 0 (ví dụ "00" -> "0")
-                    house = "0".to_string();
-                } else {
-                    house = trimmed.to_string();
+
+            if let Ok(window_lock) = app_state.window.lock() {
+                if let Some(window) = &*window_lock {
+                    let _ = window.emit("update-result", &result);
                 }
-                result.house_num = Some(house);
-            }
-            if is("floor") || is("room") {
-                result.room = Some(long.clone());
             }
-            if is("sublocality_level_1") {
-                result.buaname = Some(long.clone());
-            }
-            if is("sublocality_level_2") {
-                result.sub_com = Some(long.clone());
+
+            result
+        },
+        Err(e) => {
+            println!("Error calling API: {}", e);
+            if let Ok(window_lock) = app_state.window.lock() {
+                if let Some(window) = &*window_lock {
+                    let _ = window.emit("show-error", format!("API Error: {}", e));
+                }
             }
-            if is("route") {
-                // Chuẩn hóa unicode trước khi kiểm tra
-                let normalized_long = unicode_normalization::UnicodeNormalization::nfc(long.chars())
-                    .collect::<String>();
-                
-                let cleaned_name = if normalized_long.starts_with("Đường ") {
-                    normalized_long.replacen("Đường ", "", 1)
-                } else if normalized_long.starts_with("đường ") {
-                    normalized_long.replacen("đường ", "", 1)
-                } else if normalized_long.starts_with("Đ. ") {
-                    normalized_long.replacen("Đ. ", "", 1)
-                } else {
-                    normalized_long
-                };
-                result.st_name = Some(cleaned_name);
+            ExampleResult {
+                status: "D".into(),
+                address: format!("API Error: {}", e),
+                status_detail: Some(e.to_string()),
+                source: Some("Googlemap".to_string()),
+                explain: Some("4-Build_update".to_string()),
+                classify: Some("P-Private".to_string()),
+                ..Default::default()
             }
         }
-    }
+    };
 
-    result.phone = first["formatted_phone_number"].as_str().map(|s| s.to_string());
-    result.web = first["website"].as_str().map(|s| s.to_string());
-    result.google_id = first["place_id"].as_str().map(|s| s.to_string());
+    set_processing_state(&app_state, false);
 
-    result
+    // Ghi lịch sử ngay khi xử lý xong, không đợi editor chọn candidate
+    let history_id = record_history(&app_state, parsed.lat, parsed.lng, started, &ctx, error.as_deref(), &response_json).await;
+
+    // Có nhiều candidate: chờ editor chọn trên widget rồi mới trả về cho add-in
+    let response_json = if response_json.candidates.is_some() {
+        wait_for_selection(&app_state, parsed.lat, parsed.lng, response_json, config.candidate_timeout_secs, history_id).await
+    } else {
+        response_json
+    };
+
+    let resp_text = match serde_json::to_string(&response_json) {
+        Ok(text) => text,
+        Err(e) => {
+            println!("Error serializing response: {}", e);
+            format!("{{\"status\":\"D\",\"address\":\"Serialization error: {}\",\"source\":\"Googlemap\",\"explain\":\"4-Build_update\",\"classify\":\"P-Private\"}}", e)
+        }
+    };
+
+    json_response(resp_text)
 }
 
+// Handler cho POST /process/batch
+async fn handle_process_batch(State(app_state): State<Arc<AppState>>, body: Bytes) -> Response {
+    println!("Received batch request from Addin!");
 
-pub fn parse_placedetails_response(response: Value) -> ExampleResult {
-    let mut result = ExampleResult::default();
+    let content = match String::from_utf8(body.to_vec()) {
+        Ok(content) => content,
+        Err(e) => {
+            println!("Error reading request body: {}", e);
+            return format!("Error reading body: {}", e).into_response();
+        }
+    };
 
-    if let Some(status) = response["status"].as_str() {
-        if status != "OK" {
-            result.status = "D".to_string();
-            result.status_detail = Some(format!("Places API error: {}", status));
-            return result;
+    let items: Vec<BatchItem> = match serde_json::from_str(&content) {
+        Ok(data) => data,
+        Err(e) => {
+            println!("Error parsing JSON: {}", e);
+            return format!("Error parsing JSON: {}", e).into_response();
         }
-    }
+    };
+    println!("Batch size = {}", items.len());
 
-    let detail = &response["result"];
-
-    if let Some(name) = detail["name"].as_str() {
-        result.poi_vn = Some(name.to_string());
-    }
-
-    if let Some(phone) = detail["formatted_phone_number"].as_str() {
-        result.phone = Some(phone.to_string());
-    }
-
-    if let Some(web) = detail["website"].as_str() {
-        result.web = Some(web.to_string());
-    }
-
-    // Lấy tọa độ từ geometry.location (nếu có)
-    if let Some(loc) = detail["geometry"]["location"].as_object() {
-        result.latitude = loc.get("lat").and_then(|v| v.as_f64());
-        result.longitude = loc.get("lng").and_then(|v| v.as_f64());
-    }
-
-    // Parse type và sub_type từ types array
-    if let Some(types_array) = detail["types"].as_array() {
-        // Tạo mapping từ bảng
-        let type_mapping: Vec<(&str, &str)> = vec![
-            ("accounting", "Bu6"),
-            ("airport", "Tran5"),
-            ("amusement_park", "Rec5"),
-            ("aquarium", "Shop2"),
-            ("art_gallery", "Ent1"),
-            ("atm", "Bu1"),
-            ("bakery", "FD2"),
-            ("bank", "Bu2"),
-            ("bar", "FD1"),
-            ("beauty_salon", "Shop2"),
-            ("bicycle_store", "Shop2"),
-            ("book_store", "Se1"),
-            ("bowling_alley", ""),
-            ("bus_station", "Tran4"),
-            ("cafe", "FD3"),
-            ("campground", "Si1"),
-            ("car_dealer", "Au1"),
-            ("car_rental", "Au1"),
-            ("car_repair", "Au4"),
-            ("car_wash", "Au4"),
-            ("casino", ""),
-            ("cemetery", "Si5"),
-            ("church", "Si2"),
-            ("city_hall", ""),
-            ("clothing_store", "Shop2"),
-            ("convenience_store", "Shop2"),
-            ("courthouse", "Gov2"),
-            ("dentist", "Eme2"),
-            ("department_store", "Shop2"),
-            ("doctor", "Eme6"),
-            ("drugstore", "Eme1"),
-            ("electrician", "Shop2"),
-            ("electronics_store", "Shop2"),
-            ("embassy", "Gov6"),
-            ("fire_station", "Eme4"),
-            ("florist", "Shop2"),
-            ("funeral_home", "Si5"),
-            ("furniture_store", "Shop2"),
-            ("gas_station", "Au3"),
-            ("gym", "Rec8"),
-            ("hair_care", "Shop2"),
-            ("hardware_store", "Shop2"),
-            ("hindu_temple", "Si4"),
-            ("home_goods_store", "Shop2"),
-            ("hospital", "Eme3"),
-            ("insurance_agency", "Bu6"),
-            ("jewelry_store", "Shop2"),
-            ("laundry", "Shop2"),
-            ("lawyer", "Bu6"),
-            ("library", "Se1"),
-            ("light_rail_station", "Tran1"),
-            ("liquor_store", "FD2"),
-            ("local_government_office", "Gov7"),
-            ("locksmith", ""),
-            ("lodging", "Lod1, Lod2, Lod3"),
-            ("meal_delivery", ""),
-            ("meal_takeaway", ""),
-            ("mosque", "Si2"),
-            ("movie_rental", ""),
-            ("movie_theater", "Ent5"),
-            ("moving_company", "Bu6"),
-            ("museum", "Ent2"),
-            ("night_club", "FD1"),
-            ("painter", ""),
-            ("park", "Rec5"),
-            ("parking", "Au2"),
-            ("pet_store", ""),
-            ("pharmacy", "Eme1"),
-            ("physiotherapist", "Eme6"),
-            ("plumber", ""),
-            ("police", "Eme5"),
-            ("post_office", "Se3"),
-            ("primary_school", "Edu1"),
-            ("real_estate_agency", "Bu6"),
-            ("restaurant", "FD2"),
-            ("roofing_contractor", "Bu6"),
-            ("rv_park", "Rec5"),
-            ("school", ""),
-            ("secondary_school", "Edu2"),
-            ("shoe_store", "Shop2"),
-            ("shopping_mall", "Shop1"),
-            ("spa", "Rec4"),
-            ("stadium", "Rec3"),
-            ("storage", ""),
-            ("store", "Shop2"),
-            ("subway_station", "Tran1"),
-            ("supermarket", "Shop4"),
-            ("synagogue", "Si2"),
-            ("taxi_stand", ""),
-            ("tourist_attraction", "Si1"),
-            ("train_station", "Tran1"),
-            ("transit_station", "Tran4"),
-            ("travel_agency", "Se2"),
-            ("university", "Edu4"),
-            ("veterinary_care", "Eme6"),
-            ("zoo", "Ent6"),
-        ];
-
-        // Tìm type_google đầu tiên trong mảng types mà có trong mapping
-        for google_type in types_array {
-            if let Some(google_type_str) = google_type.as_str() {
-                if let Some(&(_, sub_type)) = type_mapping.iter()
-                    .find(|&&(type_name, _)| type_name == google_type_str) {
-                    
-                    if !google_type_str.is_empty() {
-                        result.r#type = Some(google_type_str.to_string());
-                    }
-                    
-                    if !sub_type.is_empty() {
-                        result.sub_type = Some(sub_type.to_string());
-                    }
-                    
-                    // Chỉ lấy type đầu tiên tìm thấy
-                    break;
-                }
-            }
+    set_processing_state(&app_state, true);
+
+    let config = current_config(&app_state);
+    let results = process_batch(items, &config, &app_state).await;
+
+    set_processing_state(&app_state, false);
+
+    let resp_text = match serde_json::to_string(&results) {
+        Ok(text) => text,
+        Err(e) => {
+            println!("Error serializing response: {}", e);
+            "[]".to_string()
         }
-    }
+    };
 
-    result
+    json_response(resp_text)
 }
 
+// Handler cho POST /search: trả về danh sách ExampleResult (có tọa độ) cho query
+async fn handle_search(State(app_state): State<Arc<AppState>>, body: Bytes) -> Response {
+    println!("Received search request from Addin!");
 
-async fn call_custom_api(lat: f64, lng: f64, base_url: &str, default_perform: &str) -> Result<ExampleResult, Box<dyn std::error::Error>> {
-    let mut result = call_geocode_api(lat, lng, base_url).await?;
-    
-    if !default_perform.is_empty() {
-        result.perform = Some(default_perform.to_string());
-    }
-
-    if let Some(place_id) = &result.google_id {
-        match call_placedetails_api(place_id, base_url).await {
-            Ok(details) => {
-                // --- KIỂM TRA KHOẢNG CÁCH ---
-                if let (Some(detail_lat), Some(detail_lng)) = (details.latitude, details.longitude) {
-                    let distance = haversine_distance(lat, lng, detail_lat, detail_lng);
-                    println!("Distance between original ({},{}) and place details ({},{}): {:.2} meters",
-                             lat, lng, detail_lat, detail_lng, distance);
-                    if distance > 50.0 {
-                        result.status = "D".to_string();
-                        result.status_detail = Some(format!("Không có điểm phù hợp (khoảng cách {:.1}m > 50m)", distance));
-                    }
-                } else {
-                    println!("Warning: Place Details does not contain coordinates");
-                }
-                if let Some(poi_vn) = details.poi_vn {
-                    result.poi_vn = Some(poi_vn);
-                }
-                if let Some(phone) = details.phone {
-                    result.phone = Some(phone);
-                }
-                if let Some(web) = details.web {
-                    result.web = Some(web);
-                }
-                // Thêm parse cho type và sub_type
-                if let Some(type_val) = details.r#type {
-                    result.r#type = Some(type_val);
-                }
-                if let Some(sub_type) = details.sub_type {
-                    result.sub_type = Some(sub_type);
-                }
-            }
-            Err(e) => {
-                println!("Error calling placedetails API: {}", e);
-                result.status_detail = Some(format!("Placedetails API error: {}", e));
-            }
+    let content = match String::from_utf8(body.to_vec()) {
+        Ok(content) => content,
+        Err(e) => {
+            println!("Error reading request body: {}", e);
+            return format!("Error reading body: {}", e).into_response();
         }
-    }
+    };
 
-    // Thêm ngày cập nhật (GMT+7) với định dạng dd/mm/yyyy
-    let gmt_plus_7 = FixedOffset::east_opt(7 * 3600).unwrap(); // GMT+7
-    let now_utc = Utc::now();
-    let now_gmt7 = gmt_plus_7.from_utc_datetime(&now_utc.naive_utc());
-    
-    result.update_ = Some(now_gmt7.format("%d/%m/%Y").to_string());
+    let request: SearchRequest = match serde_json::from_str(&content) {
+        Ok(data) => data,
+        Err(e) => {
+            println!("Error parsing JSON: {}", e);
+            return format!("Error parsing JSON: {}", e).into_response();
+        }
+    };
+    println!("Search query = {}", request.query);
 
+    set_processing_state(&app_state, true);
 
-    Ok(result)
-}
+    let config = current_config(&app_state);
+    let results = if config.is_configured() {
+        search_places_with_fallback(&request.query, &config, &app_state).await
+    } else {
+        Err("Base URL not configured".into())
+    };
 
-fn start_local_server(app_state: Arc<AppState>) {
-    thread::spawn(move || {
-        let server = Server::http("127.0.0.1:31203").unwrap();
-        println!("Tauri server listening on http://127.0.0.1:31203");
+    set_processing_state(&app_state, false);
 
-        loop {
-            let mut request = match server.recv() {
-                Ok(rq) => rq,
-                Err(e) => {
-                    println!("Server error: {}", e);
-                    continue;
-                }
-            };
+    let results = match results {
+        Ok(results) => results,
+        Err(e) => {
+            println!("Error searching places: {}", e);
+            vec![ExampleResult {
+                status: "D".into(),
+                status_detail: Some(format!("API Error: {}", e)),
+                ..Default::default()
+            }]
+        }
+    };
 
-            if request.method() == &Method::Post && request.url() == "/process" {
-                println!("Received request from Addin!");
+    let resp_text = match serde_json::to_string(&results) {
+        Ok(text) => text,
+        Err(e) => {
+            println!("Error serializing response: {}", e);
+            "[]".to_string()
+        }
+    };
 
-                let mut content = String::new();
-                if let Err(e) = request.as_reader().read_to_string(&mut content) {
-                    println!("Error reading request body: {}", e);
-                    let response = Response::from_string(format!("Error reading body: {}", e));
-                    request.respond(response).unwrap();
-                    continue;
-                }
-                println!("Raw data = {}", content);
+    json_response(resp_text)
+}
 
-                let parsed: IncomingData = match serde_json::from_str(&content) {
-                    Ok(data) => data,
-                    Err(e) => {
-                        println!("Error parsing JSON: {}", e);
-                        let response = Response::from_string(format!("Error parsing JSON: {}", e));
-                        request.respond(response).unwrap();
-                        continue;
-                    }
-                };
-                println!("Lat = {}, Lon = {}", parsed.lat, parsed.lng);
-
-                let state_clone = Arc::clone(&app_state);
-                
-                let (tx, rx) = tokio::sync::oneshot::channel();
-                
-                {
-                    if let Ok(mut pending) = state_clone.pending_requests.lock() {
-                        pending.push(tx);
-                    }
-                }
-                
-                {
-                    if let Ok(mut processing) = state_clone.is_processing.lock() {
-                        *processing = true;
-                    }
-                }
+// Handler cho GET /discovery: cho add-in biết cổng thực tế của service
+async fn handle_discovery(State(app_state): State<Arc<AppState>>) -> Response {
+    let info = match app_state.server_info.lock() {
+        Ok(info) => info.clone(),
+        Err(_) => None,
+    };
 
-                {
-                    if let Ok(window_lock) = state_clone.window.lock() {
-                        if let Some(window) = &*window_lock {
-                            let _ = window.emit("update-processing-state", true);
-                        }
-                    }
-                }
-                
-                let rt = tokio::runtime::Runtime::new().unwrap();
-                
-                let response_json = rt.block_on(async {
-                    let config = {
-                        if let Ok(config_lock) = state_clone.api_config.lock() {
-                            config_lock.clone()
-                        } else {
-                            ApiConfig::default()
-                        }
-                    };
+    match serde_json::to_string(&info) {
+        Ok(text) => json_response(text),
+        Err(e) => format!("Error serializing server info: {}", e).into_response(),
+    }
+}
 
-                    let result = if !config.base_url.is_empty() {
-                        call_custom_api(parsed.lat, parsed.lng, &config.base_url, &config.default_perform).await
-                    } else {
-                        Err("Base URL not configured".into())
-                    };
+// Bind vào cổng cấu hình, nếu bị chiếm thì thử các cổng kế tiếp
+async fn bind_listener(host: &str, port: u16) -> Option<tokio::net::TcpListener> {
+    for offset in 0..PORT_FALLBACK_ATTEMPTS {
+        let Some(candidate) = port.checked_add(offset) else {
+            break;
+        };
 
-                    match result {
-                        Ok(result) => {
-                            if let Ok(mut latest_data) = state_clone.latest_data.lock() {
-                                *latest_data = Some(result.clone());
-                            }
-                            
-                            if let Ok(window_lock) = state_clone.window.lock() {
-                                if let Some(window) = &*window_lock {
-                                    let _ = window.emit("update-result", &result);
-                                }
-                            }
-                            
-                            result
-                        },
-                        Err(e) => {
-                            println!("Error calling API: {}", e);
-                            if let Ok(window_lock) = state_clone.window.lock() {
-                                if let Some(window) = &*window_lock {
-                                    let _ = window.emit("show-error", format!("API Error: {}", e));
-                                }
-                            }
-                            ExampleResult {
-                                status: "D".into(), 
-                                address: format!("API Error: {}", e),
-                                source: Some("Googlemap".to_string()),
-                                explain: Some("4-Build_update".to_string()),
-                                classify: Some("P-Private".to_string()),
-                                ..Default::default()
-                            }
-                        }
-                    }
-                });
+        match tokio::net::TcpListener::bind((host, candidate)).await {
+            Ok(listener) => return Some(listener),
+            Err(e) => {
+                println!("Cannot listen on {}:{}: {}", host, candidate, e);
+            }
+        }
+    }
 
-                {
-                    if let Ok(mut processing) = state_clone.is_processing.lock() {
-                        *processing = false;
-                    }
-                }
+    None
+}
 
-                {
-                    if let Ok(window_lock) = state_clone.window.lock() {
-                        if let Some(window) = &*window_lock {
-                            let _ = window.emit("update-processing-state", false);
-                        }
-                    }
-                }
+// Route không hợp lệ: trả về nội dung giống server cũ (status 200)
+async fn handle_invalid_route() -> Response {
+    "Invalid route".into_response()
+}
 
-                let resp_text = match serde_json::to_string(&response_json) {
-                    Ok(text) => text,
-                    Err(e) => {
-                        println!("Error serializing response: {}", e);
-                        format!("{{\"status\":\"D\",\"address\":\"Serialization error: {}\",\"source\":\"Googlemap\",\"explain\":\"4-Build_update\",\"classify\":\"P-Private\"}}", e)
-                    }
-                };
+// Server HTTP cho add-in, chạy trên Tokio runtime của Tauri
+async fn start_local_server(app_state: Arc<AppState>) {
+    let app = Router::new()
+        .route("/process", post(handle_process))
+        .route("/process/batch", post(handle_process_batch))
+        .route("/search", post(handle_search))
+        .route("/discovery", get(handle_discovery))
+        .fallback(handle_invalid_route)
+        .method_not_allowed_fallback(handle_invalid_route)
+        .with_state(Arc::clone(&app_state));
+
+    let config = current_config(&app_state);
+
+    let Some(listener) = bind_listener(&config.listen_host, config.listen_port).await else {
+        println!(
+            "Server error: no free port in {}..{} on {}",
+            config.listen_port,
+            config.listen_port.saturating_add(PORT_FALLBACK_ATTEMPTS - 1),
+            config.listen_host
+        );
+        if let Ok(window_lock) = app_state.window.lock() {
+            if let Some(window) = &*window_lock {
+                let _ = window.emit("show-error", "Cannot start local server: no free port");
+            }
+        }
+        return;
+    };
 
-                let response = Response::from_string(resp_text)
-                    .with_header(Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap());
+    let port = match listener.local_addr() {
+        Ok(addr) => addr.port(),
+        Err(_) => config.listen_port,
+    };
 
-                if let Err(e) = request.respond(response) {
-                    println!("Error sending response: {}", e);
-                }
-                continue;
-            }
+    let info = ServerInfo {
+        service: "GeocoderApp".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        host: config.listen_host.clone(),
+        port,
+        pid: std::process::id(),
+    };
 
-            request
-                .respond(Response::from_string("Invalid route"))
-                .unwrap();
-        }
-    });
+    if let Err(e) = save_server_info(&info) {
+        println!("Error saving server info: {}", e);
+    }
+
+    if let Ok(mut server_info) = app_state.server_info.lock() {
+        *server_info = Some(info);
+    }
+
+    println!("Tauri server listening on http://{}:{}", config.listen_host, port);
+
+    if let Err(e) = axum::serve(listener, app).await {
+        println!("Server error: {}", e);
+    }
 }
 
 
@@ -931,9 +2077,29 @@ fn get_api_config(state: tauri::State<Arc<AppState>>) -> Result<ApiConfig, Strin
 
 // Command để cập nhật cấu hình API
 #[tauri::command]
-fn update_api_config(new_config: ApiConfig, state: tauri::State<Arc<AppState>>) -> Result<(), String> {
+fn update_api_config(mut new_config: ApiConfig, state: tauri::State<Arc<AppState>>) -> Result<(), String> {
+    new_config.migrate_type_match_distance(&current_type_mapping(&state));
+
+    // Load ranh giới mới trước khi lấy lock config (file lớn có thể mất vài giây)
+    let old_config = current_config(&state);
+    let boundaries = (old_config.boundary_file != new_config.boundary_file
+        || old_config.boundary_properties != new_config.boundary_properties)
+        .then(|| load_boundaries(&new_config));
+
     if let Ok(mut config) = state.api_config.lock() {
         *config = new_config.clone();
+        drop(config);
+
+        if let Some(index) = boundaries {
+            if let Ok(mut current) = state.boundaries.lock() {
+                *current = Arc::new(index);
+            }
+        }
+
+        // Tạo lại HTTP client để áp dụng timeout mới
+        if let Ok(mut client) = state.http_client.lock() {
+            *client = new_config.build_http_client();
+        }
         
         // Lưu cấu hình vào file
         if let Err(e) = save_config(&new_config) {
@@ -947,11 +2113,197 @@ fn update_api_config(new_config: ApiConfig, state: tauri::State<Arc<AppState>>)
     }
 }
 
+// Command để lấy thông tin server (host/cổng thực tế)
+#[tauri::command]
+fn get_server_info(state: tauri::State<Arc<AppState>>) -> Option<ServerInfo> {
+    if let Ok(info) = state.server_info.lock() {
+        info.clone()
+    } else {
+        None
+    }
+}
+
+// Command để widget tìm địa điểm theo địa chỉ hoặc tên
+#[tauri::command]
+async fn search_places(query: String, state: tauri::State<'_, Arc<AppState>>) -> Result<Vec<ExampleResult>, String> {
+    let config = current_config(&state);
+    if !config.is_configured() {
+        return Err("Base URL not configured".to_string());
+    }
+
+    search_places_with_fallback(&query, &config, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Command để editor chọn candidate (theo index trong danh sách) cho request đang chờ có id tương ứng
+#[tauri::command]
+async fn confirm_candidate(id: u64, index: usize, state: tauri::State<'_, Arc<AppState>>) -> Result<ExampleResult, String> {
+    let (pending, candidate) = {
+        let mut pending_lock = state.pending_requests.lock().map_err(|e| e.to_string())?;
+        pending_lock.retain(|p| !p.sender.is_closed());
+
+        let Some(position) = pending_lock.iter().position(|p| p.id == id) else {
+            return Err(format!("Request {} is no longer waiting for candidate selection", id));
+        };
+
+        let Some(candidate) = pending_lock[position]
+            .result
+            .candidates
+            .as_ref()
+            .and_then(|candidates| candidates.get(index))
+            .cloned()
+        else {
+            return Err(format!("Invalid candidate index: {}", index));
+        };
+
+        (pending_lock.remove(position), candidate)
+    };
+
+    let config = current_config(&state);
+    let mut selected = candidate.result;
+
+    let provider = provider_for_source(&config, selected.source.as_deref());
+    let ctx = provider_context(&config, &state);
+    complete_result(pending.lat, pending.lng, &config, &state, provider.as_deref(), &ctx, &mut selected).await;
+    apply_dup_check(&state, &config, pending.lat, pending.lng, &mut selected);
+
+    selected.perform = pending.result.perform.clone();
+    selected.update_ = pending.result.update_.clone();
+    selected.candidates = None;
+
+    update_history(&state, pending.history_id, ctx.take_responses(), &selected).await;
+
+    if let Ok(mut latest_data) = state.latest_data.lock() {
+        *latest_data = Some(selected.clone());
+    }
+
+    if let Ok(window_lock) = state.window.lock() {
+        if let Some(window) = &*window_lock {
+            let _ = window.emit("update-result", &selected);
+        }
+    }
+
+    if pending.sender.send(selected.clone()).is_err() {
+        println!("Add-in request is no longer waiting for this selection");
+    }
+
+    Ok(selected)
+}
+
+// Command để editor đóng popup chọn candidate: bỏ request khỏi hàng chờ,
+// add-in nhận ngay kết quả ban đầu thay vì chờ hết candidate_timeout_secs
+#[tauri::command]
+fn cancel_candidate_selection(id: u64, state: tauri::State<Arc<AppState>>) {
+    if let Ok(mut pending) = state.pending_requests.lock() {
+        pending.retain(|p| p.id != id);
+    }
+}
+
+// Command để đọc lại file type mapping mà không cần khởi động lại app
+#[tauri::command]
+fn reload_type_mapping(state: tauri::State<Arc<AppState>>) -> TypeMappingInfo {
+    reload_type_mapping_file(&state)
+}
+
+// Command để lấy thông tin bảng type mapping đang dùng (version, cảnh báo validate)
+#[tauri::command]
+fn get_type_mapping_info(state: tauri::State<Arc<AppState>>) -> TypeMappingInfo {
+    current_type_mapping(&state).info()
+}
+
+// Command để import điểm có sẵn trên layer (file GeoJSON hoặc CSV export) vào dup index
+#[tauri::command]
+fn import_dup_index(path: String, state: tauri::State<Arc<AppState>>) -> Result<usize, String> {
+    state.dup_index.import(Path::new(&path))
+}
+
+// Command để xóa toàn bộ dup index
+#[tauri::command]
+fn clear_dup_index(state: tauri::State<Arc<AppState>>) {
+    state.dup_index.clear();
+}
+
+// Command để lấy số điểm đang có trong dup index
+#[tauri::command]
+fn get_dup_index_size(state: tauri::State<Arc<AppState>>) -> usize {
+    state.dup_index.len()
+}
+
+// Command để liệt kê lịch sử xử lý (mới nhất trước)
+#[tauri::command]
+fn list_history(limit: Option<usize>, offset: Option<usize>, state: tauri::State<Arc<AppState>>) -> Result<Vec<HistorySummary>, String> {
+    state.history.list(limit.unwrap_or(50), offset.unwrap_or(0))
+}
+
+// Command để tìm trong lịch sử theo tên, địa chỉ, google_id hoặc lỗi
+#[tauri::command]
+fn search_history(query: String, limit: Option<usize>, state: tauri::State<Arc<AppState>>) -> Result<Vec<HistorySummary>, String> {
+    state.history.search(&query, limit.unwrap_or(50))
+}
+
+// Command để lấy đầy đủ một dòng lịch sử (response gốc và kết quả)
+#[tauri::command]
+fn get_history_entry(id: i64, state: tauri::State<Arc<AppState>>) -> Result<HistoryEntry, String> {
+    state.history.get(id)?.ok_or_else(|| format!("History entry {} not found", id))
+}
+
+// Command để mở lại kết quả cũ trên widget (làm latest_data) để editor xem hoặc gửi lại
+#[tauri::command]
+fn reopen_history_entry(id: i64, state: tauri::State<Arc<AppState>>) -> Result<ExampleResult, String> {
+    let result = state
+        .history
+        .get(id)?
+        .and_then(|entry| entry.result)
+        .ok_or_else(|| format!("History entry {} has no result", id))?;
+
+    if let Ok(mut latest_data) = state.latest_data.lock() {
+        *latest_data = Some(result.clone());
+    }
+
+    if let Ok(window_lock) = state.window.lock() {
+        if let Some(window) = &*window_lock {
+            let _ = window.emit("update-result", &result);
+        }
+    }
+
+    Ok(result)
+}
+
+// Command để export kết quả trong lịch sử ra GeoJSON, CSV hoặc KML.
+// Chọn theo danh sách id, hoặc theo khoảng ngày from/to (YYYY-MM-DD, GMT+7)
+#[tauri::command]
+fn export_results(
+    format: ExportFormat,
+    path: String,
+    ids: Option<Vec<i64>>,
+    from: Option<String>,
+    to: Option<String>,
+    state: tauri::State<Arc<AppState>>,
+) -> Result<usize, String> {
+    let entries = state.history.export_entries(ids.as_deref(), from.as_deref(), to.as_deref())?;
+    export::export(entries, format, Path::new(&path))
+}
+
+// Export các kết quả trong ngày (GMT+7) ra thư mục exports, trả về đường dẫn file
+fn export_today(app_state: &AppState, format: ExportFormat) -> Result<PathBuf, String> {
+    let gmt_plus_7 = FixedOffset::east_opt(7 * 3600).unwrap();
+    let today = Utc::now().with_timezone(&gmt_plus_7).date_naive();
+
+    let mut path = get_exports_dir().map_err(|e| e.to_string())?;
+    path.push(format!("results_{}.{}", today.format("%Y%m%d"), format.extension()));
+
+    let day = today.format("%Y-%m-%d").to_string();
+    let entries = app_state.history.export_entries(None, Some(&day), Some(&day))?;
+    export::export(entries, format, &path)?;
+    Ok(path)
+}
+
 // Command để lấy trạng thái processing
 #[tauri::command]
 fn get_processing_state(state: tauri::State<Arc<AppState>>) -> bool {
-    if let Ok(processing) = state.is_processing.lock() {
-        *processing
+    if let Ok(count) = state.processing_count.lock() {
+        *count > 0
     } else {
         false
     }
@@ -1089,13 +2441,24 @@ impl Default for ExampleResult {
             poi_st_sd: None,
             room: None,
             house_num: None,
+            alley: None,
+            sub_alley: None,
             buaname: None,
             st_name: None,
             sub_com: None,
+            province: None,
+            district: None,
+            ward: None,
             phone: None,
             fax: None,
             web: None,
             mail: None,
+            social: None,
+            intl_phone: None,
+            opening_hours: None,
+            business_status: None,
+            rating_count: None,
+            map_url: None,
             brandname: None,
             import: None,
             status_detail: None,
@@ -1114,6 +2477,10 @@ impl Default for ExampleResult {
             plus_code: None,
             latitude: None,
             longitude: None,
+            distance: None,
+            cache: None,
+            candidates: None,
+            selection_id: None,
         }
     }
 }
@@ -1122,14 +2489,33 @@ impl Default for ExampleResult {
 
 fn main() {
     // Load cấu hình từ file khi khởi động
-    let initial_config = load_config();
+    let mut initial_config = load_config();
+    let initial_type_mapping = TypeMapping::load(get_type_mapping_path().ok());
+    if initial_config.migrate_type_match_distance(&initial_type_mapping) {
+        if let Err(e) = save_config(&initial_config) {
+            println!("Error saving migrated config: {}", e);
+        }
+    }
+    let initial_boundaries = load_boundaries(&initial_config);
+
+    let http_client = initial_config.build_http_client();
 
     let app_state = Arc::new(AppState {
         window: Arc::new(Mutex::new(None)),
         latest_data: Arc::new(Mutex::new(None)),
         pending_requests: Arc::new(Mutex::new(Vec::new())),
         api_config: Arc::new(Mutex::new(initial_config)),
-        is_processing: Arc::new(Mutex::new(false)),
+        processing_count: Arc::new(Mutex::new(0)),
+        server_info: Arc::new(Mutex::new(None)),
+        cache: Arc::new(ResponseCache::load(get_cache_path().ok())),
+        dup_index: Arc::new(DupIndex::load(get_dup_index_path().ok())),
+        history: Arc::new(HistoryDb::open(get_history_path().ok())),
+        http_client: Arc::new(Mutex::new(http_client)),
+        type_mapping: Arc::new(Mutex::new(Arc::new(initial_type_mapping))),
+        address_rules: Arc::new(Mutex::new(Arc::new(AddressNormalizer::load(get_address_rules_path().ok())))),
+        admin_units: Arc::new(Mutex::new(Arc::new(AdminUnits::load(get_admin_units_path().ok())))),
+        brands: Arc::new(Mutex::new(Arc::new(BrandDictionary::load(get_brands_path().ok())))),
+        boundaries: Arc::new(Mutex::new(Arc::new(initial_boundaries))),
     });
 
     let state_clone = Arc::clone(&app_state);
@@ -1145,6 +2531,9 @@ fn main() {
             let set_perform_item = MenuItem::with_id(app, "set_perform", "Set Perform Value", true, None::<&str>)?;
             
             let select_maps_item = MenuItem::with_id(app, "select_maps", "Select Maps", true, None::<&str>)?;
+            let clear_cache_item = MenuItem::with_id(app, "clear_cache", "Clear Cache", true, None::<&str>)?;
+            let reload_rules_item = MenuItem::with_id(app, "reload_rules", "Reload Rules", true, None::<&str>)?;
+            let export_today_item = MenuItem::with_id(app, "export_today", "Export Today (CSV)", true, None::<&str>)?;
             let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
 
             let separator1 = MenuItem::with_id(app, "sep1", "---", false, None::<&str>)?;
@@ -1159,6 +2548,9 @@ fn main() {
                 &opacity_item,
                 &set_perform_item, 
                 &select_maps_item,
+                &clear_cache_item,
+                &reload_rules_item,
+                &export_today_item,
                 &separator2,
                 &quit_item,
             ])?;
@@ -1203,6 +2595,35 @@ fn main() {
                             let _ = window.show();
                             let _ = window.set_focus();
                         }
+                        "clear_cache" => {
+                            println!("Clear Cache menu item clicked");
+                            let state = app.state::<Arc<AppState>>();
+                            println!("Clearing {} cached responses", state.cache.len());
+                            state.cache.clear();
+                        }
+                        "reload_rules" => {
+                            println!("Reload Rules menu item clicked");
+                            let state = app.state::<Arc<AppState>>();
+                            reload_address_rules_file(&state);
+                            reload_admin_units_file(&state);
+                            reload_brands_file(&state);
+                            reload_boundaries(&state, &current_config(&state));
+                            let info = reload_type_mapping_file(&state);
+                            if !info.warnings.is_empty() {
+                                let _ = window.emit("show-error", format!("Type mapping: {} cảnh báo, xem log", info.warnings.len()));
+                            }
+                        }
+                        "export_today" => {
+                            println!("Export Today menu item clicked");
+                            let state = app.state::<Arc<AppState>>();
+                            match export_today(&state, ExportFormat::Csv) {
+                                Ok(path) => println!("Today's results exported to: {:?}", path),
+                                Err(e) => {
+                                    println!("Error exporting today's results: {}", e);
+                                    let _ = window.emit("show-error", format!("Export Error: {}", e));
+                                }
+                            }
+                        }
                         "quit" => {
                             println!("Quit menu item clicked");
                             app.exit(0);
@@ -1263,7 +2684,8 @@ fn main() {
                 *window_lock = Some(main_window);
             }
             
-            start_local_server(state_clone);
+            tauri::async_runtime::spawn(flush_periodically(Arc::clone(&state_clone)));
+            tauri::async_runtime::spawn(start_local_server(state_clone));
             Ok(())
         })
         .manage(app_state)
@@ -1276,10 +2698,30 @@ fn main() {
             get_api_config,
             update_api_config,
             get_processing_state,
+            get_server_info,
+            confirm_candidate,
+            cancel_candidate_selection,
+            search_places,
+            reload_type_mapping,
+            get_type_mapping_info,
+            import_dup_index,
+            clear_dup_index,
+            get_dup_index_size,
+            list_history,
+            search_history,
+            get_history_entry,
+            reopen_history_entry,
+            export_results,
             open_map_view,
             open_multiple_map_views,
             open_selected_maps,
         ])
-        .run(generate_context!())
-        .expect("error while running Tauri application");
+        .build(generate_context!())
+        .expect("error while building Tauri application")
+        .run(|app, event| {
+            // Ghi nốt cache và dup index trước khi thoát
+            if let tauri::RunEvent::Exit = event {
+                flush_state(&app.state::<Arc<AppState>>());
+            }
+        });
 }
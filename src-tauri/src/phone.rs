@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{append_note, ExampleResult};
+
+// Đầu số di động 11 số cũ (01xx) và đầu số 10 số tương ứng sau đợt chuyển đổi 2018
+const MOBILE_PREFIX_MIGRATION: &[(&str, &str)] = &[
+    // Viettel
+    ("162", "32"), ("163", "33"), ("164", "34"), ("165", "35"),
+    ("166", "36"), ("167", "37"), ("168", "38"), ("169", "39"),
+    // MobiFone
+    ("120", "70"), ("121", "79"), ("122", "77"), ("126", "76"), ("128", "78"),
+    // VinaPhone
+    ("123", "83"), ("124", "84"), ("125", "85"), ("127", "81"), ("129", "82"),
+    // Vietnamobile, Gmobile
+    ("186", "56"), ("188", "58"), ("199", "59"),
+];
+
+// Đầu số dịch vụ (tổng đài) không có mã vùng
+const SERVICE_PREFIXES: &[&str] = &["1800", "1900"];
+
+// Ký tự phân tách khi một field chứa nhiều số ("0903 123 456 / 028 3823 4567")
+const NUMBER_SEPARATORS: &[char] = &[',', ';', '/', '|', '\n'];
+
+// Format số điện thoại ghi vào kết quả, chọn trong ApiConfig
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PhoneFormat {
+    // "0903 123 456", "028 3823 4567"
+    #[default]
+    Local,
+    // "+84903123456", "+842838234567"
+    E164,
+    // "+84 903 123 456", "+84 28 3823 4567"
+    International,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhoneKind {
+    Mobile,
+    Landline,
+    Service,
+}
+
+// Số điện thoại đã chuẩn hóa: national là phần sau số 0 đầu (vd. "903123456", "2838234567")
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhoneNumber {
+    pub kind: PhoneKind,
+    pub national: String,
+}
+
+impl PhoneNumber {
+    pub fn format(&self, format: PhoneFormat) -> String {
+        if self.kind == PhoneKind::Service {
+            // Số tổng đài chỉ gọi được trong nước: "1900 1234", "1800 588 822"
+            let sizes: &[usize] = if self.national.len() == 8 { &[4, 4] } else { &[4, 3, 3] };
+            return group(&self.national, sizes);
+        }
+
+        match format {
+            PhoneFormat::Local => format!("0{}", local_group(&self.national, self.kind)),
+            PhoneFormat::E164 => format!("+84{}", self.national),
+            PhoneFormat::International => format!("+84 {}", local_group(&self.national, self.kind)),
+        }
+    }
+}
+
+// Chuẩn hóa phone/fax của result theo format. Nếu phone có nhiều số, số đầu tiên được giữ,
+// số có ghi "fax" chuyển sang fax (nếu fax trống), các số còn lại ghi vào note
+pub fn apply(result: &mut ExampleResult, format: PhoneFormat) {
+    if let Some(phone) = result.phone.take() {
+        let mut numbers = split_numbers(&phone);
+        if numbers.is_empty() {
+            // Không nhận ra số nào: giữ nguyên chuỗi gốc để editor kiểm tra
+            result.phone = Some(phone);
+        } else {
+            let (_, first) = numbers.remove(0);
+            result.phone = Some(first.format(format));
+
+            for (label, number) in numbers {
+                let formatted = number.format(format);
+                if label.to_lowercase().contains("fax") && result.fax.is_none() {
+                    result.fax = Some(formatted);
+                } else {
+                    append_note(result, &format!("SĐT khác: {}", formatted));
+                }
+            }
+        }
+    }
+
+    if let Some(fax) = result.fax.take() {
+        result.fax = Some(match split_numbers(&fax).first() {
+            Some((_, number)) => number.format(format),
+            None => fax,
+        });
+    }
+}
+
+// Tách chuỗi thành các số hợp lệ, kèm đoạn text gốc của mỗi số (để nhận nhãn "Fax")
+pub fn split_numbers(text: &str) -> Vec<(String, PhoneNumber)> {
+    text.split(NUMBER_SEPARATORS)
+        .flat_map(|segment| segment.split(" - "))
+        .filter_map(|segment| parse(segment).map(|number| (segment.trim().to_string(), number)))
+        .collect()
+}
+
+// Parse một số: "028 3823 4567", "(028) 38234567", "+84 903 123 456", "0903.123.456",
+// "01662 345 678" (đầu số cũ), "1900 1234"
+pub fn parse(text: &str) -> Option<PhoneNumber> {
+    let digits: String = text.chars().filter(|c| c.is_ascii_digit()).collect();
+    if digits.is_empty() {
+        return None;
+    }
+
+    if SERVICE_PREFIXES.iter().any(|p| digits.starts_with(p)) && (8..=10).contains(&digits.len()) {
+        return Some(PhoneNumber {
+            kind: PhoneKind::Service,
+            national: digits,
+        });
+    }
+
+    let national = if let Some(rest) = digits.strip_prefix("0084") {
+        rest
+    } else if text.trim_start().starts_with('+') || (digits.starts_with("84") && digits.len() >= 11) {
+        digits.strip_prefix("84")?
+    } else if let Some(rest) = digits.strip_prefix('0') {
+        rest
+    } else {
+        &digits
+    };
+    let national = national.trim_start_matches('0');
+
+    // Di động 11 số cũ: 01xx -> 0xx
+    if national.len() == 10 && national.starts_with('1') {
+        let (old_prefix, subscriber) = national.split_at(3);
+        let (_, new_prefix) = MOBILE_PREFIX_MIGRATION.iter().find(|(old, _)| *old == old_prefix)?;
+        return Some(PhoneNumber {
+            kind: PhoneKind::Mobile,
+            national: format!("{}{}", new_prefix, subscriber),
+        });
+    }
+
+    let kind = match (national.len(), national.chars().next()?) {
+        (9, '3' | '5' | '7' | '8' | '9') => PhoneKind::Mobile,
+        (10, '2') => PhoneKind::Landline,
+        _ => return None,
+    };
+
+    Some(PhoneNumber {
+        kind,
+        national: national.to_string(),
+    })
+}
+
+// Dạng trong nước (không có số 0 đầu): di động 3-3-3, cố định mã vùng-4-4 hoặc mã vùng-3-4
+fn local_group(national: &str, kind: PhoneKind) -> String {
+    match kind {
+        PhoneKind::Mobile => group(national, &[3, 3, 3]),
+        _ if is_three_digit_area(national) => group(national, &[3, 3, 4]),
+        _ => group(national, &[2, 4, 4]),
+    }
+}
+
+// Mã vùng 3 số (2xx) trừ Hà Nội (24) và TP.HCM (28) dùng mã 2 số
+fn is_three_digit_area(national: &str) -> bool {
+    !(national.starts_with("24") || national.starts_with("28"))
+}
+
+fn group(digits: &str, sizes: &[usize]) -> String {
+    let mut parts = Vec::new();
+    let mut rest = digits;
+
+    for size in sizes {
+        if rest.is_empty() {
+            break;
+        }
+        let (part, tail) = rest.split_at((*size).min(rest.len()));
+        parts.push(part);
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        parts.push(rest);
+    }
+
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn national(text: &str) -> Option<(PhoneKind, String)> {
+        parse(text).map(|number| (number.kind, number.national))
+    }
+
+    #[test]
+    fn parses_landline_with_spaces_and_parentheses() {
+        let expected = Some((PhoneKind::Landline, "2838234567".to_string()));
+        assert_eq!(national("028 3823 4567"), expected);
+        assert_eq!(national("(028) 38234567"), expected);
+    }
+
+    #[test]
+    fn parses_mobile_with_country_code_and_dots() {
+        let expected = Some((PhoneKind::Mobile, "903123456".to_string()));
+        assert_eq!(national("+84 903 123 456"), expected);
+        assert_eq!(national("0084 903 123 456"), expected);
+        assert_eq!(national("84903123456"), expected);
+        assert_eq!(national("0903.123.456"), expected);
+    }
+
+    #[test]
+    fn migrates_old_eleven_digit_mobile_prefix() {
+        assert_eq!(national("01662 345 678"), Some((PhoneKind::Mobile, "362345678".to_string())));
+        assert_eq!(national("+84 1223 456 789"), Some((PhoneKind::Mobile, "773456789".to_string())));
+        // Đầu số 01xx không có trong bảng chuyển đổi
+        assert_eq!(national("01112 345 678"), None);
+    }
+
+    #[test]
+    fn keeps_service_numbers_without_country_code() {
+        let hotline = parse("1900 1234").unwrap();
+        assert_eq!(hotline.kind, PhoneKind::Service);
+        assert_eq!(hotline.format(PhoneFormat::E164), "1900 1234");
+
+        let toll_free = parse("1800588822").unwrap();
+        assert_eq!(toll_free.format(PhoneFormat::International), "1800 588 822");
+    }
+
+    #[test]
+    fn rejects_invalid_numbers() {
+        assert_eq!(parse("không có"), None);
+        assert_eq!(parse("12345"), None);
+    }
+
+    #[test]
+    fn formats_each_style() {
+        let mobile = parse("0903123456").unwrap();
+        assert_eq!(mobile.format(PhoneFormat::Local), "0903 123 456");
+        assert_eq!(mobile.format(PhoneFormat::E164), "+84903123456");
+        assert_eq!(mobile.format(PhoneFormat::International), "+84 903 123 456");
+
+        let hcm = parse("02838234567").unwrap();
+        assert_eq!(hcm.format(PhoneFormat::Local), "028 3823 4567");
+        assert_eq!(hcm.format(PhoneFormat::International), "+84 28 3823 4567");
+
+        let da_nang = parse("02363821234").unwrap();
+        assert_eq!(da_nang.format(PhoneFormat::Local), "0236 382 1234");
+    }
+
+    #[test]
+    fn splits_multiple_numbers_with_labels() {
+        let numbers = split_numbers("0903 123 456 / Fax: 028 3823 4567; 1900 1234 - 0912.345.678");
+        let labels: Vec<&str> = numbers.iter().map(|(label, _)| label.as_str()).collect();
+        let nationals: Vec<&str> = numbers.iter().map(|(_, number)| number.national.as_str()).collect();
+
+        assert_eq!(labels, ["0903 123 456", "Fax: 028 3823 4567", "1900 1234", "0912.345.678"]);
+        assert_eq!(nationals, ["903123456", "2838234567", "19001234", "912345678"]);
+    }
+
+    #[test]
+    fn apply_moves_fax_and_extra_numbers() {
+        let mut result = ExampleResult {
+            phone: Some("0903 123 456, Fax 028 3823 4567, 0912 345 678".to_string()),
+            ..Default::default()
+        };
+        apply(&mut result, PhoneFormat::E164);
+
+        assert_eq!(result.phone.as_deref(), Some("+84903123456"));
+        assert_eq!(result.fax.as_deref(), Some("+842838234567"));
+        assert_eq!(result.note.as_deref(), Some("SĐT khác: +84912345678"));
+    }
+}
@@ -0,0 +1,417 @@
+use futures::future::BoxFuture;
+use serde_json::Value;
+
+use super::{cache_status, fetch_json, url_with_params, GeocodeProvider, ProviderContext, ProviderError};
+use crate::cache;
+use crate::house_number;
+use crate::type_mapping::TypeMapping;
+use crate::ExampleResult;
+
+// Provider cho proxy tương thích Google Geocoding/Places (/geocode, /placedetails)
+pub struct GoogleProvider {
+    base_url: String,
+}
+
+impl GoogleProvider {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl GeocodeProvider for GoogleProvider {
+    fn source(&self) -> &'static str {
+        "Googlemap"
+    }
+
+    fn reverse_geocode<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        lat: f64,
+        lng: f64,
+    ) -> BoxFuture<'a, Result<ExampleResult, ProviderError>> {
+        Box::pin(async move {
+            let url = format!("{}/geocode?latlng={},{}", self.base_url, lat, lng);
+            let fetched = fetch_json(ctx, cache::geocode_key("google", lat, lng), &url, cache::is_cacheable)
+                .await
+                .map_err(|e| format!("Geocode API {}", e))?;
+
+            let mut result = parse_google_geocoding_response(fetched.value);
+            result.cache = cache_status(fetched.cache_hit);
+            Ok(result)
+        })
+    }
+
+    fn localized_name<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        place_id: &'a str,
+        language: &'a str,
+    ) -> BoxFuture<'a, Result<Option<String>, ProviderError>> {
+        Box::pin(async move {
+            let url = format!("{}/placedetails?place_id={}&language={}", self.base_url, place_id, language);
+            let fetched = fetch_json(ctx, cache::placedetails_lang_key("google", place_id, language), &url, cache::is_cacheable)
+                .await
+                .map_err(|e| format!("Placedetails API ({}) {}", language, e))?;
+
+            Ok(fetched.value["result"]["name"].as_str().filter(|s| !s.is_empty()).map(|s| s.to_string()))
+        })
+    }
+
+    fn search<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        query: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ExampleResult>, ProviderError>> {
+        Box::pin(async move {
+            let url = url_with_params(&format!("{}/textsearch", self.base_url), &[("query", query)])?;
+            let fetched = fetch_json(ctx, cache::search_key("google", query), &url, cache::is_cacheable)
+                .await
+                .map_err(|e| format!("Textsearch API {}", e))?;
+
+            Ok(parse_textsearch_response(&fetched.value, &ctx.type_mapping))
+        })
+    }
+
+    fn nearby_search<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        lat: f64,
+        lng: f64,
+        radius_m: u32,
+    ) -> BoxFuture<'a, Result<Vec<ExampleResult>, ProviderError>> {
+        Box::pin(async move {
+            let url = format!("{}/nearbysearch?location={},{}&radius={}", self.base_url, lat, lng, radius_m);
+            let fetched = fetch_json(ctx, cache::nearby_key("google", lat, lng, radius_m), &url, cache::is_cacheable)
+                .await
+                .map_err(|e| format!("Nearbysearch API {}", e))?;
+
+            Ok(parse_nearbysearch_response(&fetched.value, &ctx.type_mapping))
+        })
+    }
+
+    fn reverse_geocode_candidates<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        lat: f64,
+        lng: f64,
+    ) -> BoxFuture<'a, Result<Vec<ExampleResult>, ProviderError>> {
+        Box::pin(async move {
+            let url = format!("{}/geocode?latlng={},{}", self.base_url, lat, lng);
+            let fetched = fetch_json(ctx, cache::geocode_key("google", lat, lng), &url, cache::is_cacheable)
+                .await
+                .map_err(|e| format!("Geocode API {}", e))?;
+
+            Ok(parse_google_geocoding_candidates(&fetched.value))
+        })
+    }
+
+    fn place_details<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        place_id: &'a str,
+    ) -> BoxFuture<'a, Result<ExampleResult, ProviderError>> {
+        Box::pin(async move {
+            let url = format!("{}/placedetails?place_id={}", self.base_url, place_id);
+            let fetched = fetch_json(ctx, cache::placedetails_key("google", place_id), &url, cache::is_cacheable)
+                .await
+                .map_err(|e| format!("Placedetails API {}", e))?;
+
+            let mut result = parse_placedetails_response(fetched.value, &ctx.type_mapping);
+            result.cache = cache_status(fetched.cache_hit);
+            Ok(result)
+        })
+    }
+}
+
+// ExampleResult rỗng cho một kết quả geocode (status "A")
+fn empty_geocoding_result() -> ExampleResult {
+    ExampleResult {
+        status: "A".to_string(), 
+        address: "".to_string(),
+        poi_vn: None,
+        poi_en: None,
+        poi_ex: None,
+
+        r#type: None,
+        sub_type: None,
+        poi_st_sd: None,
+
+        room: None,
+        house_num: None,
+        alley: None,
+        sub_alley: None,
+        buaname: None,
+
+        st_name: None,
+        sub_com: None,
+        province: None,
+        district: None,
+        ward: None,
+
+        phone: None,
+        fax: None,
+        web: None,
+        mail: None,
+        social: None,
+        intl_phone: None,
+        opening_hours: None,
+        business_status: None,
+        rating_count: None,
+        map_url: None,
+
+        brandname: None,
+        import: None,
+        status_detail: None,
+        note: None,
+        done: None,
+        update_: None,
+        source: Some("Googlemap".to_string()),
+        gen_type: None,
+        perform: None,
+        
+        dup: None,
+        explain: Some("4".to_string()),
+        classify: Some("P".to_string()),
+        dtrend: None,
+        google_id: None,
+        be_id: None,
+        plus_code: None,
+        latitude: None,
+        longitude: None,
+        distance: None,
+        cache: None,
+        candidates: None,
+        selection_id: None,
+    }
+}
+
+// Trả về ExampleResult lỗi nếu status của response khác "OK"
+fn geocoding_error(response: &Value) -> Option<ExampleResult> {
+    let status = response["status"].as_str()?;
+    if status == "OK" {
+        return None;
+    }
+
+    let mut result = empty_geocoding_result();
+    result.status = "D".to_string();
+    result.status_detail = Some(format!("Google API error: {}", status));
+    Some(result)
+}
+
+pub fn parse_google_geocoding_response(response: Value) -> ExampleResult {
+    if let Some(error) = geocoding_error(&response) {
+        return error;
+    }
+
+    parse_geocoding_result(&response["results"][0])
+}
+
+// Parse toàn bộ results[] thành danh sách candidate (theo thứ tự Google trả về)
+pub fn parse_google_geocoding_candidates(response: &Value) -> Vec<ExampleResult> {
+    if geocoding_error(response).is_some() {
+        return Vec::new();
+    }
+
+    response["results"]
+        .as_array()
+        .map(|results| results.iter().map(parse_geocoding_result).collect())
+        .unwrap_or_default()
+}
+
+fn parse_geocoding_result(first: &Value) -> ExampleResult {
+    let mut result = empty_geocoding_result();
+
+    result.address = first["formatted_address"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+
+    if let Some(loc) = first["geometry"]["location"].as_object() {
+        result.latitude = loc.get("lat").and_then(|v| v.as_f64());
+        result.longitude = loc.get("lng").and_then(|v| v.as_f64());
+    }
+
+    result.plus_code = first["plus_code"]["global_code"]
+        .as_str()
+        .map(|s| s.to_string());
+
+    let mut street_number: Option<String> = None;
+    let mut locality: Option<String> = None;
+
+    if let Some(components) = first["address_components"].as_array() {
+        for comp in components {
+            let long = comp["long_name"].as_str().unwrap_or("").to_string();
+            let types = comp["types"].as_array().map_or(&[] as &[serde_json::Value], |v| v);
+
+            let is = |t: &str| types.iter().any(|x| x.as_str() == Some(t));
+
+            if is("premise") || is("point_of_interest") {
+                result.poi_vn = Some(long.clone());
+            }
+            if is("street_number") {
+                street_number = Some(long.clone());
+            }
+            if is("floor") || is("room") {
+                result.room = Some(long.clone());
+            }
+            if is("sublocality_level_1") {
+                result.buaname = Some(long.clone());
+            }
+            if is("sublocality_level_2") {
+                result.sub_com = Some(long.clone());
+            }
+            if is("route") {
+                result.st_name = Some(long.clone());
+            }
+            if is("administrative_area_level_1") {
+                result.province = Some(long.clone());
+            }
+            if is("administrative_area_level_2") {
+                result.district = Some(long.clone());
+            }
+            if is("administrative_area_level_3") {
+                result.ward = Some(long.clone());
+            }
+            if is("locality") {
+                locality = Some(long.clone());
+            }
+        }
+    }
+
+    // Một số kết quả không có administrative_area_level_1/3: lấy từ locality/sublocality
+    if result.province.is_none() {
+        result.province = locality;
+    }
+    if result.ward.is_none() {
+        result.ward = result.buaname.clone();
+    }
+
+    // Tách số nhà/hẻm/ngách, không có street_number thì lấy từ formatted_address
+    house_number::apply(&mut result, street_number.as_deref());
+
+    result.phone = first["formatted_phone_number"].as_str().map(|s| s.to_string());
+    result.web = first["website"].as_str().map(|s| s.to_string());
+    result.google_id = first["place_id"].as_str().map(|s| s.to_string());
+
+    result
+}
+
+
+pub fn parse_placedetails_response(response: Value, mapping: &TypeMapping) -> ExampleResult {
+    let mut result = ExampleResult::default();
+
+    if let Some(status) = response["status"].as_str() {
+        if status != "OK" {
+            result.status = "D".to_string();
+            result.status_detail = Some(format!("Places API error: {}", status));
+            return result;
+        }
+    }
+
+    let detail = &response["result"];
+
+    if let Some(name) = detail["name"].as_str() {
+        result.poi_vn = Some(name.to_string());
+    }
+
+    if let Some(phone) = detail["formatted_phone_number"].as_str() {
+        result.phone = Some(phone.to_string());
+    }
+
+    if let Some(web) = detail["website"].as_str() {
+        result.web = Some(web.to_string());
+    }
+
+    result.intl_phone = detail["international_phone_number"].as_str().map(|s| s.to_string());
+    result.business_status = detail["business_status"].as_str().map(|s| s.to_string());
+    result.rating_count = detail["user_ratings_total"].as_u64();
+    result.map_url = detail["url"].as_str().map(|s| s.to_string());
+
+    // Giờ mở cửa theo từng ngày ("Thứ Hai: 07:00–22:00; Thứ Ba: ...")
+    if let Some(weekday_text) = detail["opening_hours"]["weekday_text"].as_array() {
+        let lines: Vec<&str> = weekday_text.iter().filter_map(|v| v.as_str()).collect();
+        if !lines.is_empty() {
+            result.opening_hours = Some(lines.join("; "));
+        }
+    }
+
+    // Lấy tọa độ từ geometry.location (nếu có)
+    if let Some(loc) = detail["geometry"]["location"].as_object() {
+        result.latitude = loc.get("lat").and_then(|v| v.as_f64());
+        result.longitude = loc.get("lng").and_then(|v| v.as_f64());
+    }
+
+    // Parse type (category cha) và sub_type từ types array
+    if let Some(types_array) = detail["types"].as_array() {
+        let mapped = mapping.map_types(types_array.iter().filter_map(|t| t.as_str()));
+        result.status_detail = mapped.review_note();
+        result.r#type = mapped.category;
+        result.sub_type = mapped.sub_type;
+    }
+
+    result
+}
+
+// Lấy results[] của response Places search (/nearbysearch, /textsearch)
+fn place_search_results(response: &Value) -> &[Value] {
+    if response["status"].as_str() != Some("OK") {
+        return &[];
+    }
+
+    response["results"].as_array().map_or(&[], |v| v)
+}
+
+fn place_types(place: &Value) -> Vec<&str> {
+    place["types"]
+        .as_array()
+        .map(|t| t.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default()
+}
+
+// Parse một phần tử results[] của Places search thành ExampleResult
+fn parse_place_search_item(place: &Value, mapping: &TypeMapping) -> ExampleResult {
+    let mut result = empty_geocoding_result();
+    result.poi_vn = place["name"].as_str().map(|s| s.to_string());
+    // textsearch trả formatted_address, nearbysearch trả vicinity
+    result.address = place["formatted_address"]
+        .as_str()
+        .or_else(|| place["vicinity"].as_str())
+        .unwrap_or_default()
+        .to_string();
+    result.google_id = place["place_id"].as_str().map(|s| s.to_string());
+
+    if let Some(loc) = place["geometry"]["location"].as_object() {
+        result.latitude = loc.get("lat").and_then(|v| v.as_f64());
+        result.longitude = loc.get("lng").and_then(|v| v.as_f64());
+    }
+
+    let mapped = mapping.map_types(place_types(place));
+    result.status_detail = mapped.review_note();
+    result.r#type = mapped.category;
+    result.sub_type = mapped.sub_type;
+
+    result
+}
+
+// Parse response /nearbysearch, chỉ giữ các establishment (bỏ route, political, ...)
+pub fn parse_nearbysearch_response(response: &Value, mapping: &TypeMapping) -> Vec<ExampleResult> {
+    place_search_results(response)
+        .iter()
+        .filter(|place| {
+            place_types(place)
+                .iter()
+                .any(|t| *t == "establishment" || *t == "point_of_interest")
+        })
+        .map(|place| parse_place_search_item(place, mapping))
+        .collect()
+}
+
+// Parse response /textsearch (cả địa chỉ lẫn tên địa điểm)
+pub fn parse_textsearch_response(response: &Value, mapping: &TypeMapping) -> Vec<ExampleResult> {
+    place_search_results(response)
+        .iter()
+        .map(|place| parse_place_search_item(place, mapping))
+        .collect()
+}
@@ -0,0 +1,140 @@
+use futures::future::BoxFuture;
+use serde_json::Value;
+
+use super::google::{parse_google_geocoding_candidates, parse_google_geocoding_response, parse_placedetails_response};
+use super::{cache_status, fetch_json, url_with_params, GeocodeProvider, ProviderContext, ProviderError};
+use crate::cache;
+use crate::ExampleResult;
+
+const DEFAULT_BASE_URL: &str = "https://rsapi.goong.io";
+
+// Provider cho Goong API: response gần giống Google nhưng cần api_key,
+// address_components không có `types` và có thêm khối `compound`
+pub struct GoongProvider {
+    base_url: String,
+    api_key: String,
+}
+
+impl GoongProvider {
+    pub fn new(base_url: &str, api_key: &str) -> Self {
+        let base_url = if base_url.is_empty() { DEFAULT_BASE_URL } else { base_url };
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+}
+
+impl GeocodeProvider for GoongProvider {
+    fn source(&self) -> &'static str {
+        "Goong"
+    }
+
+    fn reverse_geocode<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        lat: f64,
+        lng: f64,
+    ) -> BoxFuture<'a, Result<ExampleResult, ProviderError>> {
+        Box::pin(async move {
+            let url = format!("{}/Geocode?latlng={},{}&api_key={}", self.base_url, lat, lng, self.api_key);
+            let fetched = fetch_json(ctx, cache::geocode_key("goong", lat, lng), &url, cache::is_cacheable)
+                .await
+                .map_err(|e| format!("Goong Geocode API {}", e))?;
+
+            let mut result = parse_goong_geocoding_response(fetched.value);
+            result.cache = cache_status(fetched.cache_hit);
+            Ok(result)
+        })
+    }
+
+    fn search<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        query: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ExampleResult>, ProviderError>> {
+        Box::pin(async move {
+            let url = url_with_params(
+                &format!("{}/geocode", self.base_url),
+                &[("address", query), ("api_key", self.api_key.as_str())],
+            )?;
+            let fetched = fetch_json(ctx, cache::search_key("goong", query), &url, cache::is_cacheable)
+                .await
+                .map_err(|e| format!("Goong Geocode API {}", e))?;
+
+            Ok(parse_goong_geocoding_candidates(&fetched.value))
+        })
+    }
+
+    fn reverse_geocode_candidates<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        lat: f64,
+        lng: f64,
+    ) -> BoxFuture<'a, Result<Vec<ExampleResult>, ProviderError>> {
+        Box::pin(async move {
+            let url = format!("{}/Geocode?latlng={},{}&api_key={}", self.base_url, lat, lng, self.api_key);
+            let fetched = fetch_json(ctx, cache::geocode_key("goong", lat, lng), &url, cache::is_cacheable)
+                .await
+                .map_err(|e| format!("Goong Geocode API {}", e))?;
+
+            Ok(parse_goong_geocoding_candidates(&fetched.value))
+        })
+    }
+
+    fn place_details<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        place_id: &'a str,
+    ) -> BoxFuture<'a, Result<ExampleResult, ProviderError>> {
+        Box::pin(async move {
+            let url = format!("{}/Place/Detail?place_id={}&api_key={}", self.base_url, place_id, self.api_key);
+            let fetched = fetch_json(ctx, cache::placedetails_key("goong", place_id), &url, cache::is_cacheable)
+                .await
+                .map_err(|e| format!("Goong Place Detail API {}", e))?;
+
+            let mut result = parse_placedetails_response(fetched.value, &ctx.type_mapping);
+            result.cache = cache_status(fetched.cache_hit);
+            Ok(result)
+        })
+    }
+}
+
+pub fn parse_goong_geocoding_response(response: Value) -> ExampleResult {
+    let mut result = parse_google_geocoding_response(response.clone());
+    if result.status == "A" {
+        apply_goong_fields(&mut result, &response["results"][0]);
+    }
+    result
+}
+
+// Parse toàn bộ results[] kèm các field riêng của Goong
+fn parse_goong_geocoding_candidates(response: &Value) -> Vec<ExampleResult> {
+    let items = response["results"].as_array().map_or(&[] as &[Value], |v| v);
+
+    parse_google_geocoding_candidates(response)
+        .into_iter()
+        .zip(items.iter())
+        .map(|(mut result, item)| {
+            apply_goong_fields(&mut result, item);
+            result
+        })
+        .collect()
+}
+
+// Bổ sung các field riêng của Goong cho một phần tử results[]
+fn apply_goong_fields(result: &mut ExampleResult, first: &Value) {
+    // Goong trả tên địa điểm trực tiếp trong results[i].name
+    if result.poi_vn.is_none() {
+        result.poi_vn = first["name"].as_str().filter(|s| !s.is_empty()).map(|s| s.to_string());
+    }
+
+    // address_components của Goong không có types, lấy phường/xã từ compound
+    let compound = |key: &str| first["compound"][key].as_str().filter(|s| !s.is_empty()).map(|s| s.to_string());
+    if result.buaname.is_none() {
+        result.buaname = compound("commune");
+    }
+    result.province = compound("province").or(result.province.take());
+    result.district = compound("district").or(result.district.take());
+    result.ward = compound("commune").or(result.ward.take());
+}
@@ -0,0 +1,175 @@
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::{Arc, Mutex};
+
+use crate::cache::ResponseCache;
+use crate::type_mapping::TypeMapping;
+use crate::upstream::{self, RetryPolicy};
+use crate::ExampleResult;
+
+mod google;
+mod goong;
+mod nominatim;
+
+pub type ProviderError = Box<dyn std::error::Error + Send + Sync>;
+
+// Loại geocoding provider, chọn trong ApiConfig
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    // Proxy tương thích Google (/geocode, /placedetails)
+    #[default]
+    Google,
+    Nominatim,
+    Goong,
+}
+
+// Cấu hình cho một provider: URL gốc và API key (nếu cần)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderConfig {
+    pub kind: ProviderKind,
+    #[serde(default)]
+    pub base_url: String,
+    #[serde(default)]
+    pub api_key: String,
+}
+
+// Các thành phần dùng chung mà provider cần khi gọi upstream
+pub struct ProviderContext<'a> {
+    pub client: reqwest::Client,
+    pub retry: RetryPolicy,
+    pub cache: &'a ResponseCache,
+    pub cache_ttl_hours: u64,
+    pub cache_max_entries: usize,
+    pub type_mapping: Arc<TypeMapping>,
+    // Response gốc (theo cache key) đã dùng trong request này, để ghi vào lịch sử
+    pub responses: Mutex<Map<String, Value>>,
+}
+
+impl ProviderContext<'_> {
+    // Lấy các response gốc đã thu thập dưới dạng object {cache_key: response}
+    pub fn take_responses(&self) -> Value {
+        self.responses
+            .lock()
+            .map(|mut responses| Value::Object(std::mem::take(&mut *responses)))
+            .unwrap_or(Value::Null)
+    }
+
+    fn keep_response(&self, key: &str, value: &Value) {
+        if let Ok(mut responses) = self.responses.lock() {
+            responses.insert(key.to_string(), value.clone());
+        }
+    }
+}
+
+// Mỗi provider phải trả về ExampleResult theo cùng một format:
+// - reverse_geocode: địa chỉ + ID địa điểm (google_id) để tra chi tiết
+// - place_details: tên, phone, web, type/sub_type và tọa độ của địa điểm
+pub trait GeocodeProvider: Send + Sync {
+    // Giá trị ghi vào field `source` của kết quả
+    fn source(&self) -> &'static str;
+
+    fn reverse_geocode<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        lat: f64,
+        lng: f64,
+    ) -> BoxFuture<'a, Result<ExampleResult, ProviderError>>;
+
+    fn place_details<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        place_id: &'a str,
+    ) -> BoxFuture<'a, Result<ExampleResult, ProviderError>>;
+
+    // Tìm địa điểm theo địa chỉ hoặc tên (forward geocoding), kết quả có latitude/longitude
+    fn search<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        query: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ExampleResult>, ProviderError>>;
+
+    // Tên địa điểm theo ngôn ngữ `language` (vd. "en"), mặc định provider không hỗ trợ
+    fn localized_name<'a>(
+        &'a self,
+        _ctx: &'a ProviderContext<'a>,
+        _place_id: &'a str,
+        _language: &'a str,
+    ) -> BoxFuture<'a, Result<Option<String>, ProviderError>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    // Các cơ sở kinh doanh (establishment) trong bán kính radius_m quanh điểm click,
+    // mặc định provider không hỗ trợ nearby search
+    fn nearby_search<'a>(
+        &'a self,
+        _ctx: &'a ProviderContext<'a>,
+        _lat: f64,
+        _lng: f64,
+        _radius_m: u32,
+    ) -> BoxFuture<'a, Result<Vec<ExampleResult>, ProviderError>> {
+        Box::pin(async { Ok(Vec::new()) })
+    }
+
+    // Tất cả kết quả reverse geocode quanh điểm click (mặc định chỉ có kết quả đầu tiên)
+    fn reverse_geocode_candidates<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        lat: f64,
+        lng: f64,
+    ) -> BoxFuture<'a, Result<Vec<ExampleResult>, ProviderError>> {
+        Box::pin(async move {
+            let result = self.reverse_geocode(ctx, lat, lng).await?;
+            Ok(if result.status == "A" { vec![result] } else { Vec::new() })
+        })
+    }
+}
+
+pub fn build_provider(config: &ProviderConfig) -> Box<dyn GeocodeProvider> {
+    match config.kind {
+        ProviderKind::Google => Box::new(google::GoogleProvider::new(&config.base_url)),
+        ProviderKind::Nominatim => Box::new(nominatim::NominatimProvider::new(&config.base_url)),
+        ProviderKind::Goong => Box::new(goong::GoongProvider::new(&config.base_url, &config.api_key)),
+    }
+}
+
+// Response lấy từ upstream hoặc từ cache
+struct Fetched {
+    value: Value,
+    cache_hit: bool,
+}
+
+// GET JSON qua cache: trả về bản cache nếu còn hạn, nếu không thì gọi upstream
+// (có retry) và lưu lại khi `cacheable` cho phép
+async fn fetch_json(
+    ctx: &ProviderContext<'_>,
+    cache_key: String,
+    url: &str,
+    cacheable: fn(&Value) -> bool,
+) -> Result<Fetched, upstream::UpstreamError> {
+    if let Some(value) = ctx.cache.get(&cache_key, ctx.cache_ttl_hours) {
+        println!("Cache hit: {}", cache_key);
+        ctx.keep_response(&cache_key, &value);
+        return Ok(Fetched { value, cache_hit: true });
+    }
+
+    println!("Calling upstream: {}", upstream::redact_url(url));
+    let value = upstream::get_json(&ctx.client, url, ctx.retry).await?;
+
+    ctx.keep_response(&cache_key, &value);
+    if cacheable(&value) {
+        ctx.cache.insert(cache_key, value.clone(), ctx.cache_max_entries);
+    }
+
+    Ok(Fetched { value, cache_hit: false })
+}
+
+// Ghép URL với query string đã encode (query tìm kiếm có dấu, khoảng trắng, ...)
+fn url_with_params(url: &str, params: &[(&str, &str)]) -> Result<String, ProviderError> {
+    Ok(reqwest::Url::parse_with_params(url, params)?.to_string())
+}
+
+fn cache_status(hit: bool) -> Option<String> {
+    Some(if hit { "hit" } else { "miss" }.to_string())
+}
@@ -0,0 +1,150 @@
+use futures::future::BoxFuture;
+use serde_json::Value;
+
+use super::{cache_status, fetch_json, url_with_params, GeocodeProvider, ProviderContext, ProviderError};
+use crate::cache;
+use crate::house_number;
+use crate::type_mapping::TypeMapping;
+use crate::ExampleResult;
+
+const DEFAULT_BASE_URL: &str = "https://nominatim.openstreetmap.org";
+const QUERY_PARAMS: &str = "format=jsonv2&addressdetails=1&extratags=1&namedetails=1&accept-language=vi";
+
+// Provider cho Nominatim (OpenStreetMap). google_id chứa OSM ID dạng "N123", "W456"
+// để dùng lại cho bước /lookup
+pub struct NominatimProvider {
+    base_url: String,
+}
+
+impl NominatimProvider {
+    pub fn new(base_url: &str) -> Self {
+        let base_url = if base_url.is_empty() { DEFAULT_BASE_URL } else { base_url };
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl GeocodeProvider for NominatimProvider {
+    fn source(&self) -> &'static str {
+        "OpenStreetMap"
+    }
+
+    fn reverse_geocode<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        lat: f64,
+        lng: f64,
+    ) -> BoxFuture<'a, Result<ExampleResult, ProviderError>> {
+        Box::pin(async move {
+            let url = format!("{}/reverse?lat={}&lon={}&{}", self.base_url, lat, lng, QUERY_PARAMS);
+            let fetched = fetch_json(ctx, cache::geocode_key("nominatim", lat, lng), &url, |_| true)
+                .await
+                .map_err(|e| format!("Nominatim reverse API {}", e))?;
+
+            let mut result = parse_nominatim_place(&fetched.value, &ctx.type_mapping);
+            result.cache = cache_status(fetched.cache_hit);
+            Ok(result)
+        })
+    }
+
+    fn search<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        query: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<ExampleResult>, ProviderError>> {
+        Box::pin(async move {
+            let url = url_with_params(&format!("{}/search?{}", self.base_url, QUERY_PARAMS), &[("q", query)])?;
+            let fetched = fetch_json(ctx, cache::search_key("nominatim", query), &url, |_| true)
+                .await
+                .map_err(|e| format!("Nominatim search API {}", e))?;
+
+            let places = fetched.value.as_array().map_or(&[] as &[Value], |v| v);
+            Ok(places.iter().map(|place| parse_nominatim_place(place, &ctx.type_mapping)).collect())
+        })
+    }
+
+    fn place_details<'a>(
+        &'a self,
+        ctx: &'a ProviderContext<'a>,
+        place_id: &'a str,
+    ) -> BoxFuture<'a, Result<ExampleResult, ProviderError>> {
+        Box::pin(async move {
+            let url = format!("{}/lookup?osm_ids={}&{}", self.base_url, place_id, QUERY_PARAMS);
+            let fetched = fetch_json(ctx, cache::placedetails_key("nominatim", place_id), &url, |_| true)
+                .await
+                .map_err(|e| format!("Nominatim lookup API {}", e))?;
+
+            let mut result = match fetched.value.as_array().and_then(|places| places.first()) {
+                Some(place) => parse_nominatim_place(place, &ctx.type_mapping),
+                None => ExampleResult {
+                    status_detail: Some(format!("Nominatim lookup: không tìm thấy {}", place_id)),
+                    ..Default::default()
+                },
+            };
+            result.cache = cache_status(fetched.cache_hit);
+            Ok(result)
+        })
+    }
+}
+
+fn str_field(value: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|k| value[*k].as_str())
+        .find(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
+
+pub fn parse_nominatim_place(place: &Value, mapping: &TypeMapping) -> ExampleResult {
+    let mut result = ExampleResult {
+        explain: Some("4".to_string()),
+        classify: Some("P".to_string()),
+        ..Default::default()
+    };
+
+    if let Some(error) = place["error"].as_str() {
+        result.status_detail = Some(format!("Nominatim error: {}", error));
+        return result;
+    }
+
+    result.status = "A".to_string();
+    result.address = place["display_name"].as_str().unwrap_or_default().to_string();
+
+    // Nominatim trả lat/lon dạng chuỗi
+    result.latitude = place["lat"].as_str().and_then(|v| v.parse().ok());
+    result.longitude = place["lon"].as_str().and_then(|v| v.parse().ok());
+
+    if let (Some(osm_type), Some(osm_id)) = (place["osm_type"].as_str(), place["osm_id"].as_u64()) {
+        if let Some(prefix) = osm_type.chars().next() {
+            result.google_id = Some(format!("{}{}", prefix.to_ascii_uppercase(), osm_id));
+        }
+    }
+
+    let names = &place["namedetails"];
+    result.poi_vn = str_field(names, &["name:vi", "name"]).or_else(|| str_field(place, &["name"]));
+    result.poi_en = str_field(names, &["name:en"]);
+
+    let address = &place["address"];
+    result.st_name = str_field(address, &["road"]);
+    house_number::apply(&mut result, str_field(address, &["house_number"]).as_deref());
+    result.buaname = str_field(address, &["quarter", "suburb", "village"]);
+    result.province = str_field(address, &["state", "city"]);
+    result.district = str_field(address, &["city_district", "county", "district"]);
+    result.ward = str_field(address, &["suburb", "quarter", "village", "town"]);
+
+    let tags = &place["extratags"];
+    result.phone = str_field(tags, &["phone", "contact:phone"]);
+    result.web = str_field(tags, &["website", "contact:website"]);
+    result.mail = str_field(tags, &["email", "contact:email"]);
+    result.social = str_field(tags, &["contact:facebook", "facebook", "contact:zalo"]);
+    result.opening_hours = str_field(tags, &["opening_hours"]);
+
+    // Giá trị `type` của OSM (cafe, bank, hospital, ...) phần lớn trùng tên với type Google
+    let osm_types = [place["type"].as_str(), place["category"].as_str()];
+    let mapped = mapping.map_types(osm_types.into_iter().flatten());
+    result.status_detail = mapped.review_note();
+    result.r#type = mapped.category;
+    result.sub_type = mapped.sub_type;
+
+    result
+}
@@ -0,0 +1,334 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Bảng mapping mặc định đi kèm app, được ghi ra thư mục config ở lần chạy đầu
+const DEFAULT_MAPPING_JSON: &str = include_str!("../resources/type_mapping.json");
+
+// Một dòng mapping: Google type -> sub_type nội bộ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeMappingEntry {
+    pub google_type: String,
+    // Để trống nếu type được nhận diện nhưng chưa có mã sub_type
+    #[serde(default)]
+    pub sub_type: String,
+    // Khi nhiều Google type cùng khớp thì chọn entry có priority cao nhất,
+    // bằng nhau thì theo thứ tự Google trả về
+    #[serde(default)]
+    pub priority: i32,
+}
+
+// Nhóm (category) cha trong schema ArcGIS và các sub_type con của nó
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Category {
+    pub code: String,
+    pub sub_types: Vec<String>,
+}
+
+// Format file type_mapping.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TypeMappingFile {
+    version: u32,
+    #[serde(default)]
+    categories: Vec<Category>,
+    // Format cũ (v1): danh sách sub_type không kèm category, category lấy theo tiền tố chữ
+    #[serde(default)]
+    allowed_sub_types: Vec<String>,
+    // Các Google type chung chung, không cần báo khi không map được
+    #[serde(default)]
+    ignored_types: Vec<String>,
+    mappings: Vec<TypeMappingEntry>,
+}
+
+// Kết quả map danh sách Google types
+#[derive(Debug, Clone, Default)]
+pub struct MappedType {
+    // Mã category cha (FD, Shop, Eme, ...)
+    pub category: Option<String>,
+    pub sub_type: Option<String>,
+    // Các type không map được sub_type, chỉ có khi không chọn được sub_type nào
+    pub unmapped: Vec<String>,
+}
+
+impl MappedType {
+    // Ghi chú cho status_detail để editor kiểm tra thủ công
+    pub fn review_note(&self) -> Option<String> {
+        if self.unmapped.is_empty() {
+            None
+        } else {
+            Some(format!("Chưa map được type: {}", self.unmapped.join(", ")))
+        }
+    }
+}
+
+// Thông tin bảng mapping đang dùng, trả về cho widget
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeMappingInfo {
+    pub version: u32,
+    pub source: String,
+    pub entries: usize,
+    pub warnings: Vec<String>,
+}
+
+// Bảng mapping đã validate
+#[derive(Debug, Clone)]
+pub struct TypeMapping {
+    version: u32,
+    // Đường dẫn file hoặc "built-in"
+    source: String,
+    entries: HashMap<String, TypeMappingEntry>,
+    // sub_type -> mã category cha
+    parents: HashMap<String, String>,
+    ignored_types: Vec<String>,
+    warnings: Vec<String>,
+}
+
+impl TypeMapping {
+    // Bảng mặc định đi kèm app
+    pub fn builtin() -> Self {
+        Self::validate(builtin_file(), "built-in".to_string())
+    }
+
+    // Load từ type_mapping.json (hoặc type_mapping.csv cùng thư mục). Chưa có file nào
+    // thì ghi bảng mặc định ra type_mapping.json; file lỗi thì dùng bảng mặc định
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let Some(json_path) = path else {
+            return Self::builtin();
+        };
+        let csv_path = json_path.with_extension("csv");
+
+        let loaded = if json_path.exists() {
+            fs::read_to_string(&json_path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| serde_json::from_str::<TypeMappingFile>(&content).map_err(|e| e.to_string()))
+                .map(|file| (file, json_path.clone()))
+        } else if csv_path.exists() {
+            fs::read_to_string(&csv_path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| parse_csv(&content))
+                .map(|file| (file, csv_path.clone()))
+        } else {
+            write_default(&json_path);
+            return Self::builtin();
+        };
+
+        match loaded {
+            Ok((file, path)) => {
+                let mapping = Self::validate(file, path.display().to_string());
+                println!(
+                    "Loaded type mapping v{} from {} ({} entries, {} warnings)",
+                    mapping.version,
+                    mapping.source,
+                    mapping.entries.len(),
+                    mapping.warnings.len()
+                );
+                mapping
+            }
+            Err(e) => {
+                println!("Error loading type mapping: {}, using built-in mapping", e);
+                let mut mapping = Self::builtin();
+                mapping.warnings.push(format!("Không đọc được file mapping: {}", e));
+                mapping
+            }
+        }
+    }
+
+    // Bỏ/sửa các entry không hợp lệ và ghi lại cảnh báo cho từng entry
+    fn validate(file: TypeMappingFile, source: String) -> Self {
+        let mut warnings: Vec<String> = Vec::new();
+
+        // File không khai báo category (CSV, v1) thì dùng cây category mặc định
+        let categories = if file.categories.is_empty() {
+            builtin_file().categories
+        } else {
+            file.categories
+        };
+
+        let mut parents: HashMap<String, String> = HashMap::new();
+        for category in categories {
+            for sub_type in category.sub_types {
+                if let Some(previous) = parents.insert(sub_type.clone(), category.code.clone()) {
+                    warnings.push(format!("sub_type \"{}\" thuộc cả {} và {}", sub_type, previous, category.code));
+                }
+            }
+        }
+
+        for sub_type in file.allowed_sub_types {
+            parents.entry(sub_type).or_insert_with_key(|sub_type| sub_type.chars().take_while(|c| c.is_alphabetic()).collect());
+        }
+
+        let ignored_types = if file.ignored_types.is_empty() {
+            builtin_file().ignored_types
+        } else {
+            file.ignored_types
+        };
+
+        let mut entries: HashMap<String, TypeMappingEntry> = HashMap::new();
+
+        for mut entry in file.mappings {
+            entry.google_type = entry.google_type.trim().to_string();
+            entry.sub_type = entry.sub_type.trim().to_string();
+
+            if entry.google_type.is_empty() {
+                warnings.push("Bỏ qua entry không có google_type".to_string());
+                continue;
+            }
+
+            if entry.sub_type.contains(',') || entry.sub_type.contains(char::is_whitespace) {
+                warnings.push(format!("{}: sub_type \"{}\" có nhiều giá trị", entry.google_type, entry.sub_type));
+                entry.sub_type.clear();
+            } else if !entry.sub_type.is_empty() && !parents.contains_key(&entry.sub_type) {
+                warnings.push(format!("{}: sub_type \"{}\" không có trong danh sách cho phép", entry.google_type, entry.sub_type));
+                entry.sub_type.clear();
+            }
+
+            if entries.contains_key(&entry.google_type) {
+                warnings.push(format!("{}: bị khai báo trùng, dùng dòng sau cùng", entry.google_type));
+            }
+            entries.insert(entry.google_type.clone(), entry);
+        }
+
+        for warning in &warnings {
+            println!("Type mapping warning: {}", warning);
+        }
+
+        Self {
+            version: file.version,
+            source,
+            entries,
+            parents,
+            ignored_types,
+            warnings,
+        }
+    }
+
+    // Chọn sub_type cho danh sách Google types theo priority của entry (bỏ qua entry
+    // chưa có mã), category là nhóm cha của sub_type đó
+    pub fn map_types<'a>(&self, types: impl IntoIterator<Item = &'a str>) -> MappedType {
+        let types: Vec<&str> = types.into_iter().collect();
+
+        let best = types
+            .iter()
+            .enumerate()
+            .filter_map(|(index, t)| self.entries.get(*t).map(|entry| (index, entry)))
+            .filter(|(_, entry)| !entry.sub_type.is_empty())
+            .min_by_key(|(index, entry)| (-entry.priority, *index));
+
+        if let Some((_, entry)) = best {
+            return MappedType {
+                category: self.parents.get(&entry.sub_type).cloned(),
+                sub_type: Some(entry.sub_type.clone()),
+                unmapped: Vec::new(),
+            };
+        }
+
+        let unmapped = types
+            .iter()
+            .filter(|t| !t.is_empty() && !self.ignored_types.iter().any(|ignored| ignored == *t))
+            .map(|t| t.to_string())
+            .collect();
+
+        MappedType {
+            unmapped,
+            ..Default::default()
+        }
+    }
+
+    // Category cha của một sub_type ("FD3" -> "FD")
+    pub fn category_of(&self, sub_type: &str) -> Option<String> {
+        self.parents.get(sub_type).cloned()
+    }
+
+    pub fn info(&self) -> TypeMappingInfo {
+        TypeMappingInfo {
+            version: self.version,
+            source: self.source.clone(),
+            entries: self.entries.len(),
+            warnings: self.warnings.clone(),
+        }
+    }
+}
+
+fn builtin_file() -> TypeMappingFile {
+    serde_json::from_str(DEFAULT_MAPPING_JSON).expect("built-in type_mapping.json is invalid")
+}
+
+// Parse file CSV: google_type,sub_type[,priority]. Dòng "# version: N" đặt version,
+// các dòng # khác và dòng header được bỏ qua. sub_type nhiều giá trị phải đặt trong ""
+fn parse_csv(content: &str) -> Result<TypeMappingFile, String> {
+    let mut version = 0;
+    let mut mappings = Vec::new();
+
+    for (line_no, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(comment) = line.strip_prefix('#') {
+            if let Some(v) = comment.trim().strip_prefix("version:") {
+                version = v.trim().parse().map_err(|_| format!("CSV dòng {}: version không hợp lệ", line_no + 1))?;
+            }
+            continue;
+        }
+
+        let fields = split_csv_line(line);
+        if fields.first().map(|f| f.as_str()) == Some("google_type") {
+            continue;
+        }
+
+        let priority = match fields.get(2).map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            Some(p) => p.parse().map_err(|_| format!("CSV dòng {}: priority không hợp lệ", line_no + 1))?,
+            None => 0,
+        };
+
+        mappings.push(TypeMappingEntry {
+            google_type: fields.first().cloned().unwrap_or_default(),
+            sub_type: fields.get(1).cloned().unwrap_or_default(),
+            priority,
+        });
+    }
+
+    Ok(TypeMappingFile {
+        version,
+        categories: Vec::new(),
+        allowed_sub_types: Vec::new(),
+        ignored_types: Vec::new(),
+        mappings,
+    })
+}
+
+// Tách một dòng CSV theo dấu phẩy, hỗ trợ giá trị đặt trong ""
+pub fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+
+    fields
+}
+
+fn write_default(path: &Path) {
+    if let Some(dir) = path.parent() {
+        if !dir.exists() {
+            if let Err(e) = fs::create_dir_all(dir) {
+                println!("Error creating type mapping directory: {}", e);
+                return;
+            }
+        }
+    }
+
+    match fs::write(path, DEFAULT_MAPPING_JSON) {
+        Ok(_) => println!("Default type mapping written to: {:?}", path),
+        Err(e) => println!("Error writing default type mapping: {}", e),
+    }
+}
@@ -0,0 +1,131 @@
+use serde_json::Value;
+use std::fmt;
+use std::time::Duration;
+
+// Cấu hình timeout/retry cho các request tới upstream API
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub backoff_ms: u64,
+}
+
+impl RetryPolicy {
+    // Thời gian chờ trước lần thử thứ attempt (>= 2), tăng gấp đôi mỗi lần
+    pub fn delay_ms(&self, attempt: u32) -> u64 {
+        self.backoff_ms.saturating_mul(1 << attempt.saturating_sub(2).min(16))
+    }
+}
+
+#[derive(Debug)]
+pub enum UpstreamError {
+    // Đã thử hết số lần cho phép mà upstream vẫn lỗi tạm thời
+    RetriesExhausted { attempts: u32, last_error: String },
+    // Lỗi không retry được (4xx, JSON sai, ...)
+    Failed(String),
+}
+
+impl fmt::Display for UpstreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpstreamError::RetriesExhausted { attempts, last_error } => {
+                write!(f, "upstream failed after {} attempts ({})", attempts, last_error)
+            }
+            UpstreamError::Failed(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for UpstreamError {}
+
+// Tham số query chứa API key, không được ghi ra log
+const SECRET_PARAMS: &[&str] = &["key", "api_key", "apikey"];
+
+// URL để ghi log: thay giá trị các tham số API key bằng "***"
+pub fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+
+    let params: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if SECRET_PARAMS.contains(&name.to_ascii_lowercase().as_str()) => format!("{}=***", name),
+            _ => pair.to_string(),
+        })
+        .collect();
+
+    format!("{}?{}", base, params.join("&"))
+}
+
+// Tạo HTTP client dùng chung cho toàn bộ app
+pub fn build_client(connect_timeout_ms: u64, read_timeout_ms: u64) -> reqwest::Client {
+    reqwest::Client::builder()
+        // Nominatim yêu cầu User-Agent định danh ứng dụng
+        .user_agent(concat!("GeocoderApp/", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(Duration::from_millis(connect_timeout_ms))
+        .read_timeout(Duration::from_millis(read_timeout_ms))
+        .build()
+        .unwrap_or_else(|e| {
+            println!("Error building HTTP client: {}, using default", e);
+            reqwest::Client::new()
+        })
+}
+
+// GET một URL trả về JSON, retry với exponential backoff khi gặp 5xx, 429,
+// timeout/lỗi kết nối hoặc status OVER_QUERY_LIMIT trong body
+pub async fn get_json(client: &reqwest::Client, url: &str, policy: RetryPolicy) -> Result<Value, UpstreamError> {
+    let attempts = policy.max_retries + 1;
+    let mut last_error = String::new();
+
+    for attempt in 1..=attempts {
+        if attempt > 1 {
+            let delay = policy.delay_ms(attempt);
+            println!("Retrying {} in {}ms (attempt {}/{})", redact_url(url), delay, attempt, attempts);
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+        }
+
+        let response = match client.get(url).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                // Lỗi reqwest có kèm URL (chứa API key)
+                let retryable = e.is_timeout() || e.is_connect() || e.is_request();
+                let message = e.without_url().to_string();
+                if retryable {
+                    last_error = message;
+                    continue;
+                }
+                return Err(UpstreamError::Failed(message));
+            }
+        };
+
+        let status = response.status();
+        if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            last_error = format!("HTTP {}", status);
+            continue;
+        }
+        if !status.is_success() {
+            return Err(UpstreamError::Failed(format!("request failed with status: {}", status)));
+        }
+
+        let text = match response.text().await {
+            Ok(text) => text,
+            Err(e) => {
+                last_error = e.to_string();
+                continue;
+            }
+        };
+        println!("Upstream response: {}", text);
+
+        let value: Value = serde_json::from_str(&text)
+            .map_err(|e| UpstreamError::Failed(format!("invalid JSON: {}", e)))?;
+
+        if value["status"].as_str() == Some("OVER_QUERY_LIMIT") {
+            last_error = "OVER_QUERY_LIMIT".to_string();
+            continue;
+        }
+
+        return Ok(value);
+    }
+
+    Err(UpstreamError::RetriesExhausted { attempts, last_error })
+}